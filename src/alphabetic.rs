@@ -0,0 +1,150 @@
+// Alphabetic numbering, for the "a" and "A" format tokens: a, b, ... z, aa,
+// ab, ... and likewise for other alphabets, such as Greek for "α" and "Α".
+// This is bijective base n for an alphabet of n letters, so there's no letter
+// for zero, and zero is formatted as a decimal number instead. We write the
+// letters in lower case; upper case is applied afterwards.
+use ibig::{ops::DivRem, UBig};
+
+use crate::casing::Casing;
+use crate::fingerprint::Fingerprinter;
+
+const LATIN: [char; 26] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+
+// the final sigma (ς) isn't a letter of the sequence
+const GREEK: [char; 24] = [
+    'α', 'β', 'γ', 'δ', 'ε', 'ζ', 'η', 'θ', 'ι', 'κ', 'λ', 'μ', 'ν', 'ξ', 'ο', 'π', 'ρ', 'σ', 'τ',
+    'υ', 'φ', 'χ', 'ψ', 'ω',
+];
+
+// The alphabets we can number with. To add one, give its lower case letters
+// in order; they have to be ascending code points so that the output stays
+// sortable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Alphabet {
+    Latin,
+    Greek,
+}
+
+impl Alphabet {
+    // the alphabet whose first letter is given, in either case
+    pub(crate) fn new(first: char) -> Option<(Self, Casing)> {
+        match first {
+            'a' => Some((Alphabet::Latin, Casing::Lower)),
+            'A' => Some((Alphabet::Latin, Casing::Upper)),
+            'α' => Some((Alphabet::Greek, Casing::Lower)),
+            'Α' => Some((Alphabet::Greek, Casing::Upper)),
+            _ => None,
+        }
+    }
+
+    fn letters(self) -> &'static [char] {
+        match self {
+            Alphabet::Latin => &LATIN,
+            Alphabet::Greek => &GREEK,
+        }
+    }
+
+    // the maximum length in bytes of a letter, in either case
+    pub(crate) const fn max_letter_len(self) -> usize {
+        match self {
+            Alphabet::Latin => 1,
+            Alphabet::Greek => 2,
+        }
+    }
+
+    pub(crate) fn fingerprint(&self, f: &mut Fingerprinter) {
+        f.write_u8(match self {
+            Alphabet::Latin => 0,
+            Alphabet::Greek => 1,
+        });
+    }
+
+    // append the letters for the value given as ascii digits, or return false
+    // if the value is zero as there are no letters for it
+    pub(crate) fn write(self, digits: &str, output: &mut String) -> bool {
+        let Ok(mut n) = UBig::from_str_radix(digits, 10) else {
+            return false;
+        };
+        let letters = self.letters();
+        let base = UBig::from(letters.len());
+        let zero = UBig::from(0u8);
+        // the letters come out from the right
+        let mut indices = Vec::new();
+        while n != zero {
+            n -= 1u8;
+            let (quotient, remainder) = n.div_rem(&base);
+            // the remainder is below the amount of letters
+            indices.push(usize::try_from(&remainder).unwrap_or(0));
+            n = quotient;
+        }
+        output.extend(indices.iter().rev().map(|index| letters[*index]));
+        !indices.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use icu::locid::LanguageIdentifier;
+
+    fn letters(digits: &str) -> Option<String> {
+        in_alphabet(Alphabet::Latin, digits)
+    }
+
+    fn in_alphabet(alphabet: Alphabet, digits: &str) -> Option<String> {
+        let mut output = String::new();
+        alphabet.write(digits, &mut output).then_some(output)
+    }
+
+    #[test]
+    fn test_write() {
+        assert_eq!(letters("1").unwrap(), "a");
+        assert_eq!(letters("26").unwrap(), "z");
+        assert_eq!(letters("27").unwrap(), "aa");
+        assert_eq!(letters("28").unwrap(), "ab");
+        assert_eq!(letters("52").unwrap(), "az");
+        assert_eq!(letters("53").unwrap(), "ba");
+        assert_eq!(letters("702").unwrap(), "zz");
+        assert_eq!(letters("703").unwrap(), "aaa");
+    }
+
+    #[test]
+    fn test_zero() {
+        assert_eq!(letters("0"), None);
+    }
+
+    #[test]
+    fn test_large() {
+        // 26^20 + ... + 26 + 1 is twenty-one a's
+        let mut value = UBig::from(0u8);
+        for _ in 0..21 {
+            value = value * 26u8 + 1u8;
+        }
+        assert_eq!(letters(&value.to_string()).unwrap(), "a".repeat(21));
+    }
+
+    #[test]
+    fn test_greek() {
+        assert_eq!(in_alphabet(Alphabet::Greek, "1").unwrap(), "α");
+        assert_eq!(in_alphabet(Alphabet::Greek, "18").unwrap(), "σ");
+        assert_eq!(in_alphabet(Alphabet::Greek, "24").unwrap(), "ω");
+        assert_eq!(in_alphabet(Alphabet::Greek, "25").unwrap(), "αα");
+        assert_eq!(in_alphabet(Alphabet::Greek, "0"), None);
+    }
+
+    #[test]
+    fn test_letters_ascend() {
+        for alphabet in [Alphabet::Latin, Alphabet::Greek] {
+            let letters = alphabet.letters();
+            assert!(letters.windows(2).all(|pair| pair[0] < pair[1]));
+            for letter in letters {
+                let mut upper = letter.to_string();
+                Casing::Upper.apply(&mut upper, &LanguageIdentifier::UND);
+                assert!(upper.len() <= alphabet.max_letter_len());
+            }
+        }
+    }
+}
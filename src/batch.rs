@@ -0,0 +1,219 @@
+// Formatting a whole column of values with a single picture, producing
+// buffers laid out like an arrow StringArray, so data export pipelines can
+// hand them over without copying each string.
+use crate::error::Error;
+use crate::format_integer::Picture;
+use crate::integer::with_digits;
+use crate::options::FormatOptions;
+
+#[derive(Debug, PartialEq)]
+pub struct FormattedColumn {
+    // arrow layout: string i is values[offsets[i]..offsets[i + 1]]
+    offsets: Vec<i32>,
+    values: String,
+    // arrow layout: bit i (least significant bit first) is set if value i
+    // is valid. None means all values are valid.
+    validity: Option<Vec<u8>>,
+}
+
+impl FormattedColumn {
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn offsets(&self) -> &[i32] {
+        &self.offsets
+    }
+
+    pub fn values(&self) -> &str {
+        &self.values
+    }
+
+    pub fn validity(&self) -> Option<&[u8]> {
+        self.validity.as_deref()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        if index >= self.len() || !is_valid(self.validity.as_deref(), index) {
+            return None;
+        }
+        let start = self.offsets[index] as usize;
+        let end = self.offsets[index + 1] as usize;
+        Some(&self.values[start..end])
+    }
+}
+
+fn is_valid(validity: Option<&[u8]>, index: usize) -> bool {
+    validity.is_none_or(|validity| validity[index / 8] & (1 << (index % 8)) != 0)
+}
+
+pub fn format_integer_column(
+    values: &[i64],
+    validity: Option<&[u8]>,
+    picture: &str,
+) -> Result<FormattedColumn, Error> {
+    format_integer_column_with_options(values, validity, picture, &FormatOptions::default())
+}
+
+// as format_integer_column, taking the options into account. A value whose
+// output is longer than the limit fails the whole column.
+pub fn format_integer_column_with_options(
+    values: &[i64],
+    validity: Option<&[u8]>,
+    picture: &str,
+    options: &FormatOptions,
+) -> Result<FormattedColumn, Error> {
+    check_validity(values, validity)?;
+    // the picture is only compiled once for the whole column
+    let picture = Picture::parse_with_options(picture, options)?;
+    format_column(values, validity, &picture, options, None)
+}
+
+// the validity bitmap needs a bit for every value
+pub(crate) fn check_validity(values: &[i64], validity: Option<&[u8]>) -> Result<(), Error> {
+    match validity {
+        Some(validity) if validity.len() * 8 < values.len() => Err(Error::InvalidValidityBitmap),
+        _ => Ok(()),
+    }
+}
+
+// format the values with a compiled picture, once the validity bitmap has
+// been checked, applying the postprocessor to every value if there is one
+pub(crate) fn format_column(
+    values: &[i64],
+    validity: Option<&[u8]>,
+    picture: &Picture,
+    options: &FormatOptions,
+    postprocessor: Option<&dyn Fn(String) -> String>,
+) -> Result<FormattedColumn, Error> {
+    let mut offsets = Vec::with_capacity(values.len() + 1);
+    let mut output = String::new();
+    // the values are written straight into the output; the buffers are
+    // only used by pictures that need them, and reused for every value
+    let (mut digits, mut scratch) = (String::new(), String::new());
+    offsets.push(0);
+    for (index, value) in values.iter().enumerate() {
+        // null values are represented as empty strings in the buffer,
+        // just like arrow does
+        if is_valid(validity, index) {
+            let start = output.len();
+            with_digits(value, &mut digits, |is_negative, digits| {
+                picture.format_digits_with_options(
+                    is_negative,
+                    digits,
+                    options,
+                    &mut scratch,
+                    &mut output,
+                )
+            })?;
+            if let Some(postprocessor) = postprocessor {
+                let postprocessed = postprocessor(output[start..].to_string());
+                output.truncate(start);
+                output.push_str(&postprocessed);
+            }
+        }
+        offsets.push(i32::try_from(output.len()).map_err(|_| Error::OutputTooLong)?);
+    }
+    Ok(FormattedColumn {
+        offsets,
+        values: output,
+        validity: validity.map(|validity| validity[..values.len().div_ceil(8)].to_vec()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::PictureError;
+
+    #[test]
+    fn test_format_integer_column() {
+        let column = format_integer_column(&[1, 1234, -56], None, "#,##0").unwrap();
+        assert_eq!(column.len(), 3);
+        assert_eq!(column.offsets(), &[0, 1, 6, 9]);
+        assert_eq!(column.values(), "11,234-56");
+        assert_eq!(column.validity(), None);
+        assert_eq!(column.get(0), Some("1"));
+        assert_eq!(column.get(1), Some("1,234"));
+        assert_eq!(column.get(2), Some("-56"));
+        assert_eq!(column.get(3), None);
+    }
+
+    #[test]
+    fn test_format_integer_column_with_nulls() {
+        // the second value is null
+        let column = format_integer_column(&[1, 2, 3], Some(&[0b101]), "00").unwrap();
+        assert_eq!(column.offsets(), &[0, 2, 2, 4]);
+        assert_eq!(column.values(), "0103");
+        assert_eq!(column.validity(), Some(&[0b101][..]));
+        assert_eq!(column.get(0), Some("01"));
+        assert_eq!(column.get(1), None);
+        assert_eq!(column.get(2), Some("03"));
+    }
+
+    #[test]
+    fn test_format_integer_column_empty() {
+        let column = format_integer_column(&[], None, "0").unwrap();
+        assert!(column.is_empty());
+        assert_eq!(column.offsets(), &[0]);
+    }
+
+    #[test]
+    fn test_format_integer_column_validity_too_short() {
+        assert_eq!(
+            format_integer_column(&[0; 9], Some(&[0xff]), "0"),
+            Err(Error::InvalidValidityBitmap)
+        );
+    }
+
+    #[test]
+    fn test_format_integer_column_invalid_picture() {
+        assert_eq!(
+            format_integer_column(&[1], None, "0,"),
+            Err(Error::InvalidPictureString(
+                PictureError::new(1, Some(',')).with_suggestion("0".to_string())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_format_integer_column_with_options() {
+        let options = FormatOptions::builder()
+            .language("de")
+            .accounting(true)
+            .build()
+            .unwrap();
+        let column = format_integer_column_with_options(&[3, -3, 30], None, "w", &options).unwrap();
+        assert_eq!(column.get(0), Some("drei"));
+        assert_eq!(column.get(1), Some("(drei)"));
+        assert_eq!(column.get(2), Some("dreißig"));
+        let column = format_integer_column_with_options(&[-1234], None, "#,##0", &options).unwrap();
+        assert_eq!(column.get(0), Some("(1,234)"));
+    }
+
+    #[test]
+    fn test_format_integer_column_max_output_len() {
+        let options = FormatOptions::builder().max_output_len(3).build().unwrap();
+        assert_eq!(
+            format_integer_column_with_options(&[1, 123], None, "0", &options)
+                .unwrap()
+                .values(),
+            "1123"
+        );
+        assert_eq!(
+            format_integer_column_with_options(&[1, 1234], None, "0", &options),
+            Err(Error::OutputTooLong)
+        );
+    }
+
+    #[test]
+    fn test_format_integer_column_extremes() {
+        let column = format_integer_column(&[i64::MIN, i64::MAX], None, "0").unwrap();
+        assert_eq!(column.get(0), Some("-9223372036854775808"));
+        assert_eq!(column.get(1), Some("9223372036854775807"));
+    }
+}
@@ -0,0 +1,136 @@
+// Changing the case of text the way the format modifiers and tokens do,
+// following the rules of a language, like the dotted capital I of Turkish.
+// Spelled-out numbers go through this, and hosts that format names of
+// months and days can use it to case them the same way.
+use icu::casemap::titlecase::TitlecaseOptions;
+use icu::casemap::CaseMapper;
+use icu::locid::LanguageIdentifier;
+
+use crate::fingerprint::Fingerprinter;
+use crate::language::Language;
+
+// the text in upper case, following the rules of the language if there is
+// one
+pub fn to_uppercase(text: &str, language: Option<&Language>) -> String {
+    CaseMapper::new().uppercase_to_string(text, identifier(language))
+}
+
+// the text in lower case, following the rules of the language if there is
+// one
+pub fn to_lowercase(text: &str, language: Option<&Language>) -> String {
+    CaseMapper::new().lowercase_to_string(text, identifier(language))
+}
+
+// the text with every word starting with a capital and the rest of it in
+// lower case, as for the "Nn" and "Ww" tokens. Words are separated by
+// whitespace, which is kept as it is. A word starts at its first cased
+// letter, so combining marks and leading punctuation stay where they are.
+pub fn to_titlecase(text: &str, language: Option<&Language>) -> String {
+    title_words(text, identifier(language), |_| true)
+}
+
+// the rules without a language
+static ROOT: LanguageIdentifier = LanguageIdentifier::UND;
+
+fn identifier(language: Option<&Language>) -> &LanguageIdentifier {
+    language.map_or(&ROOT, Language::identifier)
+}
+
+// the text with the words for which capitalize returns true in title case
+fn title_words(
+    text: &str,
+    language: &LanguageIdentifier,
+    capitalize: impl Fn(&str) -> bool,
+) -> String {
+    let mapper = CaseMapper::new();
+    let mut title = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        let word_len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (word, after) = rest.split_at(word_len);
+        if capitalize(word) {
+            title.push_str(&mapper.titlecase_segment_with_only_case_data_to_string(
+                word,
+                language,
+                TitlecaseOptions::default(),
+            ));
+        } else {
+            title.push_str(word);
+        }
+        let space_len = after
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(after.len());
+        title.push_str(&after[..space_len]);
+        rest = &after[space_len..];
+    }
+    title
+}
+
+// how the words are capitalized, as given by the format token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Casing {
+    Lower,
+    Upper,
+    // every word starts with a capital, except for "and"
+    Title,
+}
+
+impl Casing {
+    pub(crate) fn fingerprint(&self, f: &mut Fingerprinter) {
+        f.write_u8(match self {
+            Casing::Lower => 0,
+            Casing::Upper => 1,
+            Casing::Title => 2,
+        });
+    }
+
+    // apply the casing to lower case words, following the rules of the
+    // language they're in
+    pub(crate) fn apply(&self, words: &mut String, language: &LanguageIdentifier) {
+        match self {
+            Casing::Lower => {}
+            Casing::Upper => *words = CaseMapper::new().uppercase_to_string(words, language),
+            Casing::Title => *words = title_words(words, language, |word| word != "and"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turkish() {
+        let turkish = Language::parse("tr").unwrap();
+        assert_eq!(to_uppercase("iki bin", Some(&turkish)), "İKİ BİN");
+        assert_eq!(to_titlecase("iki bin", Some(&turkish)), "İki Bin");
+        assert_eq!(to_lowercase("IKI BİN", Some(&turkish)), "ıkı bin");
+        assert_eq!(to_uppercase("iki bin", None), "IKI BIN");
+    }
+
+    #[test]
+    fn test_titlecase() {
+        assert_eq!(to_titlecase("monday", None), "Monday");
+        assert_eq!(
+            to_titlecase("MONDAY  the\tfirst", None),
+            "Monday  The\tFirst"
+        );
+        assert_eq!(to_titlecase("'twas", None), "'Twas");
+        assert_eq!(to_titlecase("", None), "");
+        let dutch = Language::parse("nl").unwrap();
+        assert_eq!(to_titlecase("ijsland", Some(&dutch)), "IJsland");
+    }
+
+    #[test]
+    fn test_apply() {
+        let root = LanguageIdentifier::UND;
+        let mut words = "one hundred and twenty-three".to_string();
+        Casing::Title.apply(&mut words, &root);
+        assert_eq!(words, "One Hundred and Twenty-three");
+        Casing::Upper.apply(&mut words, &root);
+        assert_eq!(words, "ONE HUNDRED AND TWENTY-THREE");
+        let mut words = "一".to_string();
+        Casing::Title.apply(&mut words, &root);
+        assert_eq!(words, "一");
+    }
+}
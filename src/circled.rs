@@ -0,0 +1,59 @@
+// Circled numbers, for the "①" format token. Unicode has them from 0 to 50,
+// in a few runs of consecutive code points; other values are formatted as
+// decimal numbers instead.
+
+// the first code point of each run, with the value it stands for and how
+// many there are
+const RUNS: [(u32, u8, u8); 4] = [
+    // ⓪
+    (0x24ea, 0, 1),
+    // ① to ⑳
+    (0x2460, 1, 20),
+    // ㉑ to ㉟
+    (0x3251, 21, 15),
+    // ㊱ to ㊿
+    (0x32b1, 36, 15),
+];
+
+// the maximum length in bytes of a circled number
+pub(crate) const MAX_LEN: usize = 3;
+
+// the circled number for the ascii digits, if there is one
+pub(crate) fn circled(digits: &str) -> Option<char> {
+    let digits = digits.trim_start_matches('0');
+    if digits.len() > 2 {
+        return None;
+    }
+    let value = digits
+        .bytes()
+        .fold(0, |value, digit| value * 10 + (digit - b'0'));
+    RUNS.iter()
+        .find(|(_, first, count)| (*first..*first + *count).contains(&value))
+        .and_then(|(start, first, _)| char::from_u32(start + u32::from(value - first)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circled() {
+        assert_eq!(circled("0"), Some('⓪'));
+        assert_eq!(circled("1"), Some('①'));
+        assert_eq!(circled("20"), Some('⑳'));
+        assert_eq!(circled("21"), Some('㉑'));
+        assert_eq!(circled("35"), Some('㉟'));
+        assert_eq!(circled("36"), Some('㊱'));
+        assert_eq!(circled("050"), Some('㊿'));
+        assert_eq!(circled("51"), None);
+        assert_eq!(circled("100"), None);
+    }
+
+    #[test]
+    fn test_max_len() {
+        for value in 0..=50 {
+            let c = circled(&value.to_string()).unwrap();
+            assert!(c.len_utf8() <= MAX_LEN);
+        }
+    }
+}
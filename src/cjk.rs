@@ -0,0 +1,190 @@
+// CJK numerals, for the "一" and "壹" format tokens: the digits are followed by
+// their place, as in "三百二十一" (321). The places repeat for every four
+// digits, which are followed by the myriad they count, as in "十二万" (12 ×
+// 10,000). Values beyond the largest myriad are formatted as decimal
+// numbers instead.
+use crate::fingerprint::Fingerprinter;
+
+// the amount of myriads every numeral system has
+const MYRIAD_COUNT: usize = 4;
+
+// the most digits we write: four for every myriad and four below
+const MAX_DIGITS: usize = (MYRIAD_COUNT + 1) * 4;
+
+// the maximum length in bytes of a numeral, for 9999京9999兆...9999: seven
+// characters for every group of four digits and one for every myriad, each
+// taking three bytes. Zeros are only written between other digits, so they
+// never make a numeral longer.
+pub(crate) const MAX_LEN: usize = ((MYRIAD_COUNT + 1) * 7 + MYRIAD_COUNT) * 3;
+
+// The CJK numeral systems we can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CjkNumerals {
+    // the everyday Japanese numerals, where a one before a place is left
+    // out ("十" rather than "一十")
+    Kanji,
+    // the Chinese financial numerals, which can't be altered by adding
+    // strokes. Every one is written ("壹拾"), and so is a zero between
+    // other digits ("壹仟零壹").
+    Financial,
+}
+
+impl CjkNumerals {
+    // the numerals whose format token is given
+    pub(crate) fn new(token: char) -> Option<Self> {
+        match token {
+            '一' => Some(CjkNumerals::Kanji),
+            '壹' => Some(CjkNumerals::Financial),
+            _ => None,
+        }
+    }
+
+    fn digits(self) -> &'static [char; 10] {
+        match self {
+            CjkNumerals::Kanji => &['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'],
+            CjkNumerals::Financial => &['零', '壹', '貳', '參', '肆', '伍', '陸', '柒', '捌', '玖'],
+        }
+    }
+
+    // ten, hundred and thousand
+    fn places(self) -> &'static [char; 3] {
+        match self {
+            CjkNumerals::Kanji => &['十', '百', '千'],
+            CjkNumerals::Financial => &['拾', '佰', '仟'],
+        }
+    }
+
+    // ten thousand, and every four digits beyond it
+    fn myriads(self) -> &'static [char; MYRIAD_COUNT] {
+        match self {
+            CjkNumerals::Kanji => &['万', '億', '兆', '京'],
+            CjkNumerals::Financial => &['萬', '億', '兆', '京'],
+        }
+    }
+
+    pub(crate) fn fingerprint(&self, f: &mut Fingerprinter) {
+        f.write_u8(match self {
+            CjkNumerals::Kanji => 0,
+            CjkNumerals::Financial => 1,
+        });
+    }
+
+    // append the numeral for the value given as ascii digits, or return
+    // false if it's too large to write
+    pub(crate) fn write(self, digits: &str, output: &mut String) -> bool {
+        let digits = digits.trim_start_matches('0');
+        if digits.len() > MAX_DIGITS {
+            return false;
+        }
+        let (numerals, places, myriads) = (self.digits(), self.places(), self.myriads());
+        let is_financial = self == CjkNumerals::Financial;
+        if digits.is_empty() {
+            output.push(numerals[0]);
+            return true;
+        }
+        let digits = digits.as_bytes();
+        // the groups of four digits, from the left; the first one may be
+        // shorter
+        let group_count = digits.len().div_ceil(4);
+        let mut end = digits.len() - (group_count - 1) * 4;
+        let mut start = 0;
+        // whether we skipped zeros since the last digit we wrote
+        let mut skipped_zeros = false;
+        for myriad in (0..group_count).rev() {
+            let group = &digits[start..end];
+            if group.iter().any(|digit| *digit != b'0') {
+                for (index, digit) in group.iter().enumerate() {
+                    let digit = usize::from(digit - b'0');
+                    let place = group.len() - 1 - index;
+                    if digit == 0 {
+                        skipped_zeros = true;
+                        continue;
+                    }
+                    if is_financial && skipped_zeros {
+                        output.push(numerals[0]);
+                    }
+                    skipped_zeros = false;
+                    if digit > 1 || place == 0 || is_financial {
+                        output.push(numerals[digit]);
+                    }
+                    if place > 0 {
+                        output.push(places[place - 1]);
+                    }
+                }
+                if myriad > 0 {
+                    output.push(myriads[myriad - 1]);
+                }
+            } else {
+                skipped_zeros = true;
+            }
+            start = end;
+            end += 4;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kanji(digits: &str) -> Option<String> {
+        let mut output = String::new();
+        CjkNumerals::Kanji
+            .write(digits, &mut output)
+            .then_some(output)
+    }
+
+    #[test]
+    fn test_write() {
+        assert_eq!(kanji("0").unwrap(), "〇");
+        assert_eq!(kanji("1").unwrap(), "一");
+        assert_eq!(kanji("10").unwrap(), "十");
+        assert_eq!(kanji("11").unwrap(), "十一");
+        assert_eq!(kanji("21").unwrap(), "二十一");
+        assert_eq!(kanji("101").unwrap(), "百一");
+        assert_eq!(kanji("1234").unwrap(), "千二百三十四");
+    }
+
+    #[test]
+    fn test_myriads() {
+        assert_eq!(kanji("10000").unwrap(), "一万");
+        assert_eq!(kanji("120000").unwrap(), "十二万");
+        assert_eq!(kanji("100010000").unwrap(), "一億一万");
+        assert_eq!(
+            kanji("123456789").unwrap(),
+            "一億二千三百四十五万六千七百八十九"
+        );
+    }
+
+    fn financial(digits: &str) -> String {
+        let mut output = String::new();
+        CjkNumerals::Financial.write(digits, &mut output);
+        output
+    }
+
+    #[test]
+    fn test_financial() {
+        assert_eq!(financial("0"), "零");
+        assert_eq!(financial("10"), "壹拾");
+        assert_eq!(financial("1234"), "壹仟貳佰參拾肆");
+        assert_eq!(financial("1001"), "壹仟零壹");
+        assert_eq!(financial("1100"), "壹仟壹佰");
+        assert_eq!(financial("10010"), "壹萬零壹拾");
+        assert_eq!(financial("100000001"), "壹億零壹");
+        assert_eq!(financial("100010000"), "壹億零壹萬");
+    }
+
+    #[test]
+    fn test_too_large() {
+        assert!(kanji(&"9".repeat(20)).is_some());
+        assert_eq!(kanji(&format!("1{}", "0".repeat(20))), None);
+    }
+
+    #[test]
+    fn test_max_len() {
+        assert_eq!(kanji(&"9".repeat(MAX_DIGITS)).unwrap().len(), MAX_LEN);
+        assert_eq!(financial(&"9".repeat(MAX_DIGITS)).len(), MAX_LEN);
+        assert!(financial(&"9090".repeat(MAX_DIGITS / 4)).len() <= MAX_LEN);
+    }
+}
@@ -0,0 +1,598 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use ibig::IBig;
+
+#[cfg(feature = "batch")]
+use crate::batch::{check_validity, format_column, FormattedColumn};
+use crate::error::Error;
+use crate::format_integer::Picture;
+use crate::formatter::Formatter;
+use crate::info::PictureInfo;
+use crate::integer::Integer;
+use crate::language::Language;
+use crate::lint::{findings, Finding};
+use crate::options::FormatOptions;
+use crate::sink::{FormatSink, WriteSink};
+
+// A context shared between formatting calls. It holds the default options
+// and caches compiled pictures, so that formatting many values with the same
+// picture only parses it once. It can be shared between threads. The cache
+// never affects the output: a compiled picture only depends on the picture
+// and the options, so formatting through the context is the same as
+// formatting with format_integer_with_options, whatever is cached.
+//
+// Pictures are often built from data, so the cache is bounded: once it
+// holds 1024 pictures, or as many as set with with_cache_capacity, it's
+// emptied before the next one is added. Pictures that are in use stay
+// valid; the ones used again are simply compiled again.
+#[derive(Debug)]
+pub struct FormatContext {
+    options: FormatOptions,
+    integer_pictures: RwLock<HashMap<String, Arc<Picture>>>,
+    cache_capacity: usize,
+    integer_postprocessor: Option<Postprocessor>,
+}
+
+// how many compiled pictures a context holds on to by default
+const DEFAULT_CACHE_CAPACITY: usize = 1024;
+
+impl Default for FormatContext {
+    fn default() -> Self {
+        Self::new(FormatOptions::default())
+    }
+}
+
+// a function applied to formatted output before it's returned
+#[derive(Clone)]
+struct Postprocessor(Arc<dyn Fn(String) -> String + Send + Sync>);
+
+impl fmt::Debug for Postprocessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Postprocessor")
+    }
+}
+
+impl FormatContext {
+    pub fn new(options: FormatOptions) -> Self {
+        Self {
+            options,
+            integer_pictures: RwLock::new(HashMap::new()),
+            cache_capacity: DEFAULT_CACHE_CAPACITY,
+            integer_postprocessor: None,
+        }
+    }
+
+    // hold on to at most capacity compiled pictures; with 0, no picture is
+    // cached and every call parses its picture
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity;
+        self
+    }
+
+    // forget all compiled pictures, like when the host knows the pictures
+    // it formatted with won't be used again
+    pub fn clear_cache(&self) {
+        self.integer_pictures
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    // apply a function to the output of format_integer before returning
+    // it, like wrapping it in markup or normalizing it, so the host doesn't
+    // have to go over it again. The limit on the output length applies to
+    // the output before the function.
+    pub fn with_integer_postprocessor(
+        mut self,
+        postprocessor: impl Fn(String) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.integer_postprocessor = Some(Postprocessor(Arc::new(postprocessor)));
+        self
+    }
+
+    pub fn options(&self) -> &FormatOptions {
+        &self.options
+    }
+
+    pub fn format_integer(&self, i: IBig, picture: &str) -> Result<String, Error> {
+        let formatted = self
+            .integer_picture(picture)?
+            .format_with_options(i, &self.options)?;
+        Ok(self.postprocess_integer(formatted))
+    }
+
+    // as format_integer, but in the given language instead of the one of
+    // the options
+    pub fn format_integer_lang(
+        &self,
+        i: IBig,
+        picture: &str,
+        language: &Language,
+    ) -> Result<String, Error> {
+        let formatted = self
+            .integer_picture(picture)?
+            .format_with_options(i, &self.options.with_language(language.clone()))?;
+        Ok(self.postprocess_integer(formatted))
+    }
+
+    // as format_integer, but for any integer type
+    pub fn format_integer_value<I: Integer + ?Sized>(
+        &self,
+        i: &I,
+        picture: &str,
+    ) -> Result<String, Error> {
+        let formatted = self
+            .integer_picture(picture)?
+            .format_value_with_options(i, &self.options)?;
+        Ok(self.postprocess_integer(formatted))
+    }
+
+    // as format_integer, but handing the output in chunks to the sink, as
+    // Picture::format_to_sink does. The output length is limited as for
+    // format_integer, and nothing is handed over if it's too long. The
+    // postprocessor isn't applied, as it works on whole strings.
+    pub fn format_integer_to_sink<S: FormatSink + ?Sized>(
+        &self,
+        i: &IBig,
+        picture: &str,
+        sink: &mut S,
+    ) -> Result<(), Error> {
+        self.integer_picture(picture)?
+            .format_to_sink_with_options(i, &self.options, sink)
+    }
+
+    // as format_integer, but appending the output to out. The outer error
+    // is about the picture or the output length; the inner one is passed
+    // on from out. With a postprocessor, the output is postprocessed as a
+    // string before it's appended.
+    pub fn format_integer_into<W: fmt::Write + ?Sized>(
+        &self,
+        i: &IBig,
+        picture: &str,
+        out: &mut W,
+    ) -> Result<fmt::Result, Error> {
+        if self.integer_postprocessor.is_some() {
+            return Ok(out.write_str(&self.format_integer(i.clone(), picture)?));
+        }
+        let mut sink = WriteSink::new(out);
+        self.format_integer_to_sink(i, picture, &mut sink)?;
+        Ok(sink.finish())
+    }
+
+    // as format_integer, but into the buffers of the formatter, so that
+    // formatting many values doesn't allocate. The output length is
+    // limited as for format_integer. The postprocessor isn't applied, as the
+    // output stays in the formatter.
+    pub fn format_integer_with<'f>(
+        &self,
+        formatter: &'f mut Formatter,
+        i: &IBig,
+        picture: &str,
+    ) -> Result<&'f str, Error> {
+        let picture = self.integer_picture(picture)?;
+        formatter.format_checked(&picture, i, &self.options)
+    }
+
+    // as format_integer_column_with_options, with the options of the
+    // context and a cached picture. The postprocessor is applied to every
+    // value, which then has to be formatted on its own.
+    #[cfg(feature = "batch")]
+    pub fn format_integer_column(
+        &self,
+        values: &[i64],
+        validity: Option<&[u8]>,
+        picture: &str,
+    ) -> Result<FormattedColumn, Error> {
+        check_validity(values, validity)?;
+        let picture = self.integer_picture(picture)?;
+        let postprocessor =
+            self.integer_postprocessor
+                .as_ref()
+                .map(|Postprocessor(postprocessor)| {
+                    postprocessor.as_ref() as &dyn Fn(String) -> String
+                });
+        format_column(values, validity, &picture, &self.options, postprocessor)
+    }
+
+    // as validate_integer_picture, but with the options of the context,
+    // which decide whether extensions and unsupported features are allowed.
+    // The compiled picture is cached for formatting with it.
+    pub fn validate_integer_picture(&self, picture: &str) -> Result<PictureInfo, Error> {
+        Ok(self.integer_picture(picture)?.info())
+    }
+
+    // as lint, but with the options of the context, as for
+    // validate_integer_picture
+    pub fn lint(&self, picture: &str) -> Result<Vec<Finding>, Error> {
+        self.integer_picture(picture)?;
+        Ok(findings(picture))
+    }
+
+    fn postprocess_integer(&self, formatted: String) -> String {
+        match &self.integer_postprocessor {
+            Some(Postprocessor(postprocessor)) => postprocessor(formatted),
+            None => formatted,
+        }
+    }
+
+    fn integer_picture(&self, picture: &str) -> Result<Arc<Picture>, Error> {
+        // a poisoned lock can only mean another thread panicked while
+        // inserting; the map itself is still consistent so we keep using it
+        if let Some(compiled) = self
+            .integer_pictures
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(picture)
+        {
+            return Ok(compiled.clone());
+        }
+        // invalid pictures aren't cached; we report the error each time
+        let compiled = Arc::new(Picture::parse_with_options(picture, &self.options)?);
+        if self.cache_capacity == 0 {
+            return Ok(compiled);
+        }
+        let mut integer_pictures = self
+            .integer_pictures
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        if integer_pictures.len() >= self.cache_capacity && !integer_pictures.contains_key(picture)
+        {
+            integer_pictures.clear();
+        }
+        // another thread may have compiled the picture in the meantime, in
+        // which case we keep its picture, so everyone shares the same one
+        Ok(integer_pictures
+            .entry(picture.to_string())
+            .or_insert(compiled)
+            .clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::PictureError;
+    use crate::format_integer::format_integer_with_options;
+    use crate::sink::ChunkKind;
+
+    #[test]
+    fn test_format_integer() {
+        let context = FormatContext::default();
+        assert_eq!(
+            context.format_integer(1234.into(), "#,##0").unwrap(),
+            "1,234"
+        );
+        assert_eq!(
+            context.format_integer(5678.into(), "#,##0").unwrap(),
+            "5,678"
+        );
+    }
+
+    #[test]
+    fn test_picture_is_cached() {
+        let context = FormatContext::default();
+        let a = context.integer_picture("0,000").unwrap();
+        let b = context.integer_picture("0,000").unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        let c = context.integer_picture("0.000").unwrap();
+        assert!(!Arc::ptr_eq(&a, &c));
+    }
+
+    #[test]
+    fn test_invalid_picture_is_not_cached() {
+        let context = FormatContext::default();
+        assert_eq!(
+            context.format_integer(1.into(), "0,"),
+            Err(Error::InvalidPictureString(
+                PictureError::new(1, Some(',')).with_suggestion("0".to_string())
+            ))
+        );
+        assert_eq!(
+            context.format_integer(1.into(), "0,"),
+            Err(Error::InvalidPictureString(
+                PictureError::new(1, Some(',')).with_suggestion("0".to_string())
+            ))
+        );
+        assert!(context.integer_pictures.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_options_are_applied() {
+        let options = FormatOptions::builder().max_output_len(3).build().unwrap();
+        let context = FormatContext::new(options);
+        assert_eq!(context.format_integer(123.into(), "0").unwrap(), "123");
+        assert_eq!(
+            context.format_integer(1234.into(), "0"),
+            Err(Error::OutputTooLong)
+        );
+    }
+
+    #[test]
+    fn test_options() {
+        let options = FormatOptions::builder().language("de").build().unwrap();
+        let context = FormatContext::new(options.clone());
+        assert_eq!(context.options(), &options);
+    }
+
+    #[test]
+    fn test_format_integer_lang() {
+        let context = FormatContext::default();
+        let german = Language::parse("de").unwrap();
+        assert_eq!(
+            context.format_integer_lang(2.into(), "w", &german).unwrap(),
+            "zwei"
+        );
+        assert_eq!(context.format_integer(2.into(), "w").unwrap(), "two");
+    }
+
+    #[test]
+    fn test_integer_postprocessor() {
+        let context = FormatContext::default()
+            .with_integer_postprocessor(|formatted| format!("<n>{}</n>", formatted));
+        assert_eq!(
+            context.format_integer(1234.into(), "#,##0").unwrap(),
+            "<n>1,234</n>"
+        );
+        let german = Language::parse("de").unwrap();
+        assert_eq!(
+            context.format_integer_lang(2.into(), "w", &german).unwrap(),
+            "<n>zwei</n>"
+        );
+        // errors aren't postprocessed
+        assert_eq!(
+            context.format_integer(1.into(), "0,"),
+            Err(Error::InvalidPictureString(
+                PictureError::new(1, Some(',')).with_suggestion("0".to_string())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_cache_capacity() {
+        let context = FormatContext::default().with_cache_capacity(2);
+        let a = context.integer_picture("0").unwrap();
+        context.integer_picture("00").unwrap();
+        assert_eq!(context.integer_pictures.read().unwrap().len(), 2);
+        // a cached picture doesn't make room
+        assert!(Arc::ptr_eq(&a, &context.integer_picture("0").unwrap()));
+        context.integer_picture("000").unwrap();
+        assert_eq!(context.integer_pictures.read().unwrap().len(), 1);
+        assert!(!Arc::ptr_eq(&a, &context.integer_picture("0").unwrap()));
+        assert_eq!(context.format_integer(7.into(), "000").unwrap(), "007");
+    }
+
+    #[test]
+    fn test_no_cache() {
+        let context = FormatContext::default().with_cache_capacity(0);
+        assert_eq!(context.format_integer(7.into(), "00").unwrap(), "07");
+        assert!(context.integer_pictures.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_clear_cache() {
+        let context = FormatContext::default();
+        context.format_integer(1.into(), "0").unwrap();
+        context.format_integer(1.into(), "w").unwrap();
+        context.clear_cache();
+        assert!(context.integer_pictures.read().unwrap().is_empty());
+        assert_eq!(context.format_integer(1.into(), "w").unwrap(), "one");
+    }
+
+    #[test]
+    fn test_format_integer_value() {
+        let options = FormatOptions::builder()
+            .language("de")
+            .max_output_len(4)
+            .build()
+            .unwrap();
+        let context = FormatContext::new(options)
+            .with_integer_postprocessor(|formatted| format!("[{}]", formatted));
+        assert_eq!(context.format_integer_value(&3u8, "w").unwrap(), "[drei]");
+        assert_eq!(
+            context.format_integer_value(&-1234i64, "#,##0"),
+            Err(Error::OutputTooLong)
+        );
+    }
+
+    #[test]
+    fn test_format_integer_to_sink() {
+        let options = FormatOptions::builder().digit_family('٠').build().unwrap();
+        let context = FormatContext::new(options);
+        let mut chunks: Vec<(ChunkKind, String)> = Vec::new();
+        context
+            .format_integer_to_sink(&(-42).into(), "0", &mut chunks)
+            .unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                (ChunkKind::Sign, "-".to_string()),
+                (ChunkKind::Digits, "٤٢".to_string())
+            ]
+        );
+        assert!(context
+            .format_integer_to_sink(&1.into(), "0,", &mut chunks)
+            .is_err());
+    }
+
+    #[test]
+    fn test_format_integer_into() {
+        let options = FormatOptions::builder().language("de").build().unwrap();
+        let context = FormatContext::new(options);
+        let mut output = String::new();
+        context
+            .format_integer_into(&2.into(), "w", &mut output)
+            .unwrap()
+            .unwrap();
+        context
+            .format_integer_into(&3.into(), "w", &mut output)
+            .unwrap()
+            .unwrap();
+        assert_eq!(output, "zweidrei");
+        assert!(context
+            .format_integer_into(&1.into(), "0,", &mut output)
+            .is_err());
+    }
+
+    #[test]
+    fn test_limits_apply_to_every_method() {
+        let options = FormatOptions::builder().max_output_len(4).build().unwrap();
+        let context = FormatContext::new(options);
+        let mut chunks: Vec<(ChunkKind, String)> = Vec::new();
+        let mut output = String::new();
+        let mut formatter = Formatter::new();
+        for picture in ["#,##0", "w"] {
+            assert_eq!(
+                context.format_integer_value(&-12345i64, picture),
+                Err(Error::OutputTooLong)
+            );
+            assert_eq!(
+                context.format_integer_to_sink(&(-12345).into(), picture, &mut chunks),
+                Err(Error::OutputTooLong)
+            );
+            assert_eq!(
+                context.format_integer_into(&(-12345).into(), picture, &mut output),
+                Err(Error::OutputTooLong)
+            );
+            assert_eq!(
+                context.format_integer_with(&mut formatter, &(-12345).into(), picture),
+                Err(Error::OutputTooLong)
+            );
+        }
+        // nothing was handed over
+        assert!(chunks.is_empty());
+        assert!(output.is_empty());
+        assert_eq!(
+            context.format_integer_with(&mut formatter, &12.into(), "#,##0"),
+            Ok("12")
+        );
+        assert_eq!(
+            context.format_integer_with(&mut formatter, &2.into(), "w"),
+            Ok("two")
+        );
+    }
+
+    #[test]
+    fn test_format_integer_into_postprocessor() {
+        let context = FormatContext::default()
+            .with_integer_postprocessor(|formatted| format!("<n>{}</n>", formatted));
+        let mut output = "> ".to_string();
+        context
+            .format_integer_into(&1234.into(), "#,##0", &mut output)
+            .unwrap()
+            .unwrap();
+        assert_eq!(output, "> <n>1,234</n>");
+    }
+
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_format_integer_column() {
+        let options = FormatOptions::builder()
+            .language("de")
+            .max_output_len(12)
+            .build()
+            .unwrap();
+        let context = FormatContext::new(options)
+            .with_integer_postprocessor(|formatted| formatted.to_uppercase());
+        let column = context
+            .format_integer_column(&[2, 3], Some(&[0b01]), "w")
+            .unwrap();
+        assert_eq!(column.get(0), Some("ZWEI"));
+        assert_eq!(column.get(1), None);
+        assert_eq!(column.values(), "ZWEI");
+        assert_eq!(
+            context.format_integer_column(&[-1234567], None, "w"),
+            Err(Error::OutputTooLong)
+        );
+        assert_eq!(
+            context.format_integer_column(&[1; 9], Some(&[0xff]), "w"),
+            Err(Error::InvalidValidityBitmap)
+        );
+    }
+
+    #[test]
+    fn test_validate_and_lint_with_options() {
+        let context = FormatContext::default();
+        assert!(context.validate_integer_picture("+0").is_err());
+        assert!(context.lint("+0").is_err());
+        let options = FormatOptions::builder().extensions(true).build().unwrap();
+        let context = FormatContext::new(options);
+        let info = context.validate_integer_picture("+#,##0").unwrap();
+        assert_eq!(info.kind(), crate::info::TokenKind::DecimalDigits);
+        assert_eq!(
+            context.lint("###,##0").unwrap(),
+            crate::lint::lint("###,##0").unwrap()
+        );
+        assert_eq!(context.integer_pictures.read().unwrap().len(), 2);
+    }
+
+    // formatting the values with the pictures, in an order depending on
+    // the round, so that threads interleave differently
+    fn format_all(
+        format: impl Fn(IBig, &str) -> Result<String, Error>,
+        round: usize,
+    ) -> Vec<Result<String, Error>> {
+        const PICTURES: [&str; 8] = ["#,##0", "w", "Ww;o", "i", "0001", "a", "0,", "#,##,##0"];
+        const VALUES: [i64; 6] = [0, 1, -42, 1234, 99999, 1234567];
+        let mut results = Vec::new();
+        for index in 0..PICTURES.len() * VALUES.len() {
+            let index = (index + round * 7) % (PICTURES.len() * VALUES.len());
+            let picture = PICTURES[index % PICTURES.len()];
+            let value = VALUES[index / PICTURES.len()];
+            results.push(format(value.into(), picture));
+        }
+        results
+    }
+
+    #[test]
+    fn test_cache_is_shared_between_threads() {
+        let options = FormatOptions::builder().language("de").build().unwrap();
+        let context = FormatContext::new(options.clone());
+        std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..8)
+                .map(|round| {
+                    let context = &context;
+                    scope.spawn(move || {
+                        let cached = (0..20)
+                            .map(|_| format_all(|i, p| context.format_integer(i, p), round))
+                            .collect::<Vec<_>>();
+                        (round, cached)
+                    })
+                })
+                .collect();
+            for thread in threads {
+                let (round, cached) = thread.join().unwrap();
+                let uncached =
+                    format_all(|i, p| format_integer_with_options(i, p, &options), round);
+                for results in cached {
+                    assert_eq!(results, uncached);
+                }
+            }
+        });
+        // every valid picture was compiled once and is shared
+        assert_eq!(context.integer_pictures.read().unwrap().len(), 7);
+    }
+
+    #[test]
+    fn test_cache_does_not_leak_between_languages() {
+        let context = FormatContext::default();
+        let german = Language::parse("de").unwrap();
+        let korean = Language::parse("ko").unwrap();
+        for _ in 0..3 {
+            assert_eq!(context.format_integer(3.into(), "w;o").unwrap(), "third");
+            assert_eq!(
+                context
+                    .format_integer_lang(3.into(), "w;o", &german)
+                    .unwrap(),
+                "dritte"
+            );
+            assert_eq!(
+                context
+                    .format_integer_lang(3.into(), "w;o", &korean)
+                    .unwrap(),
+                "제삼"
+            );
+        }
+    }
+}
@@ -1,45 +1,80 @@
-use icu::properties::{sets::CodePointSetData, GeneralCategory};
+use icu::properties::GeneralCategory;
 
+use crate::error::Error;
+
+// an ascii digit, stored as its value. The value is always below 10.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) struct AsciiDigit(char);
+pub(crate) struct AsciiDigit(u8);
 
 impl AsciiDigit {
-    pub(crate) fn new(c: char) -> Self {
-        debug_assert!(c.is_ascii_digit());
-        AsciiDigit(c)
+    pub(crate) fn new(c: char) -> Option<Self> {
+        // to_digit with radix 10 only accepts ascii digits
+        c.to_digit(10).map(|d| AsciiDigit(d as u8))
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) struct DigitFamily(char);
+pub(crate) struct DigitFamily {
+    // the digits 0 to 9 of this family, which we construct up front so that
+    // translating a digit can't fail
+    digits: [char; 10],
+}
 
 impl DigitFamily {
     pub(crate) fn new(c: char) -> Option<Self> {
         let gc = icu::properties::maps::general_category();
-        // decimal digits can consist in multiple unicode ranges (the ascii digits
-        // versus the other ones.
-        for r in gc.iter_ranges_for_value(GeneralCategory::DecimalNumber) {
-            let c = c as u32;
-            // if the character is in the range, we first subtract the start of the
-            // range so we can do an integer division by 10, and then add the start
-            // back again. This will get us the 0 digit in that range.
-            if r.contains(&c) {
-                let index = c - r.start();
-                // we don't expect from_u32 to ever return None, but since this
-                // function is fallible anyway we can just return None and avoid
-                // an unwrap.
-                return char::from_u32(index / 10 + r.start()).map(DigitFamily);
-            }
+        if gc.get(c) != GeneralCategory::DecimalNumber {
+            return None;
+        }
+        // decimal digits come in runs of one or more families of ten (the
+        // mathematical digits have five families in a row, for instance).
+        // We find the start of the run c is in, and from there the 0 digit
+        // of c's family.
+        let c = c as u32;
+        let mut start = c;
+        while start > 0 && gc.get32(start - 1) == GeneralCategory::DecimalNumber {
+            start -= 1;
         }
-        None
+        let family = Self::from_zero(start + (c - start) / 10 * 10)?;
+        // if a run isn't made of whole families, the last one would extend
+        // beyond the run; we reject that rather than produce non-digits
+        family
+            .digits
+            .iter()
+            .all(|d| gc.get(*d) == GeneralCategory::DecimalNumber)
+            .then_some(family)
+    }
+
+    fn from_zero(zero: u32) -> Option<Self> {
+        let mut digits = ['0'; 10];
+        for (n, digit) in (0..).zip(digits.iter_mut()) {
+            *digit = char::from_u32(zero.checked_add(n)?)?;
+        }
+        Some(DigitFamily { digits })
+    }
+
+    pub(crate) const fn zero(&self) -> char {
+        self.digits[0]
     }
 
     pub(crate) fn digit(&self, d: AsciiDigit) -> char {
-        let num = d.0 as u32 - '0' as u32;
-        char::from_u32(num + self.0 as u32).unwrap()
+        self.digits[d.0 as usize]
     }
 }
 
+// the ascii digits of the string written in the family of the given digit,
+// for hosts that want digits in another script outside of pictures, like
+// page numbers. Any digit of the family will do, as in a picture; other
+// characters are kept as they are. A family digit that isn't a decimal
+// digit is an error rather than a panic, as it usually comes from
+// configuration, and formatting never panics.
+pub fn transliterate_digits(s: &str, family_digit: char) -> Result<String, Error> {
+    let family = DigitFamily::new(family_digit).ok_or(Error::InvalidDigitFamily(family_digit))?;
+    Ok(s.chars()
+        .map(|c| AsciiDigit::new(c).map_or(c, |digit| family.digit(digit)))
+        .collect())
+}
+
 pub(crate) fn is_group_separator(c: char) -> bool {
     let category = icu::properties::maps::general_category().get(c);
     //  Nd, Nl, No, Lu, Ll, Lt, Lm or Lo are not allowed to be group separators
@@ -67,14 +102,14 @@ mod tests {
 
     #[test]
     fn test_digit_family() {
-        assert_eq!(DigitFamily::new('1'), Some(DigitFamily('0')));
+        assert_eq!(DigitFamily::new('1').unwrap().zero(), '0');
         assert_eq!(
-            DigitFamily::new(ARAB_INDIC_DIGIT_ONE),
-            Some(DigitFamily(ARAB_INDIC_DIGIT_ZERO))
+            DigitFamily::new(ARAB_INDIC_DIGIT_ONE).unwrap().zero(),
+            ARAB_INDIC_DIGIT_ZERO
         );
         assert_eq!(
-            DigitFamily::new(NKO_DIGIT_FIVE),
-            Some(DigitFamily(NKO_DIGIT_ZERO))
+            DigitFamily::new(NKO_DIGIT_FIVE).unwrap().zero(),
+            NKO_DIGIT_ZERO
         );
         assert_eq!(DigitFamily::new('a'), None);
     }
@@ -84,17 +119,96 @@ mod tests {
         assert_eq!(
             DigitFamily::new(ARAB_INDIC_DIGIT_ZERO)
                 .unwrap()
-                .digit(AsciiDigit::new('1')),
+                .digit(AsciiDigit::new('1').unwrap()),
             ARAB_INDIC_DIGIT_ONE
         );
         assert_eq!(
             DigitFamily::new(NKO_DIGIT_ZERO)
                 .unwrap()
-                .digit(AsciiDigit::new('5')),
+                .digit(AsciiDigit::new('5').unwrap()),
             NKO_DIGIT_FIVE
         );
     }
 
+    #[test]
+    fn test_digit_family_in_run_of_families() {
+        // the mathematical digits are five families in one run: bold,
+        // double-struck, sans-serif, sans-serif bold and monospace
+        const MATHEMATICAL_DOUBLE_STRUCK_DIGIT_ZERO: char = '𝟘';
+        const MATHEMATICAL_DOUBLE_STRUCK_DIGIT_SEVEN: char = '𝟟';
+        const MATHEMATICAL_MONOSPACE_DIGIT_ZERO: char = '𝟶';
+        const MATHEMATICAL_MONOSPACE_DIGIT_NINE: char = '𝟿';
+        assert_eq!(
+            DigitFamily::new(MATHEMATICAL_DOUBLE_STRUCK_DIGIT_SEVEN)
+                .unwrap()
+                .zero(),
+            MATHEMATICAL_DOUBLE_STRUCK_DIGIT_ZERO
+        );
+        assert_eq!(
+            DigitFamily::new(MATHEMATICAL_MONOSPACE_DIGIT_NINE)
+                .unwrap()
+                .zero(),
+            MATHEMATICAL_MONOSPACE_DIGIT_ZERO
+        );
+    }
+
+    #[test]
+    fn test_every_decimal_digit_run_consists_of_whole_families() {
+        let gc = icu::properties::maps::general_category();
+        for r in gc.iter_ranges_for_value(GeneralCategory::DecimalNumber) {
+            assert_eq!(
+                (r.end() - r.start() + 1) % 10,
+                0,
+                "range {:X}..={:X}",
+                r.start(),
+                r.end()
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_digit_family_validates() {
+        let gc = icu::properties::maps::general_category();
+        for r in gc.iter_ranges_for_value(GeneralCategory::DecimalNumber) {
+            for zero in r.clone().step_by(10) {
+                let zero = char::from_u32(zero).unwrap();
+                let family = DigitFamily::new(zero).unwrap();
+                assert_eq!(family.zero(), zero);
+                for (n, c) in ('0'..='9').enumerate() {
+                    let digit = family.digit(AsciiDigit::new(c).unwrap());
+                    assert_eq!(digit as u32, zero as u32 + n as u32);
+                    // every digit of the family finds the same family
+                    assert_eq!(DigitFamily::new(digit), Some(family));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ascii_digit() {
+        assert_eq!(AsciiDigit::new('7'), Some(AsciiDigit(7)));
+        assert_eq!(AsciiDigit::new(ARAB_INDIC_DIGIT_ONE), None);
+        assert_eq!(AsciiDigit::new('a'), None);
+    }
+
+    #[test]
+    fn test_transliterate_digits() {
+        assert_eq!(
+            transliterate_digits("page 12 of 30", ARAB_INDIC_DIGIT_ZERO).unwrap(),
+            "page ١٢ of ٣٠"
+        );
+        assert_eq!(
+            transliterate_digits("5", NKO_DIGIT_FIVE).unwrap(),
+            NKO_DIGIT_FIVE.to_string()
+        );
+        // digits of other families are kept
+        assert_eq!(transliterate_digits("١2", '0').unwrap(), "١2");
+        assert_eq!(
+            transliterate_digits("12", 'x'),
+            Err(Error::InvalidDigitFamily('x'))
+        );
+    }
+
     #[test]
     fn test_is_group_separator() {
         assert!(is_group_separator('!'));
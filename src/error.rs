@@ -0,0 +1,491 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    InvalidPictureString(PictureError),
+    // the format modifier after the last semicolon is invalid, while the
+    // primary format token before it may be fine
+    InvalidFormatModifier(PictureError),
+    InvalidLanguageTag(String),
+    InvalidCountry(String),
+    InvalidDigitFamily(char),
+    InvalidCurrencyCode(String),
+    // two options that can't be used together
+    ConflictingOptions(&'static str, &'static str),
+    InvalidValidityBitmap,
+    OutputTooLong,
+    // the picture is valid, but asks for something we don't implement. This
+    // is only reported in strict mode; otherwise we fall back to what the
+    // specification prescribes.
+    Unsupported(Feature),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Feature {
+    // a numbering sequence given by its format token, like "a" or "i"
+    NumberingSequence(String),
+}
+
+// Where a picture is invalid: the position of the character that makes it
+// so, which is a byte offset for pictures parsed from strings, and in the
+// host's coordinates for pictures parsed from characters with positions.
+// When what's missing is at the end of the picture, or of a part of it
+// like the primary format token, there's no character; the position is
+// just past the end, or where the empty part is. For common mistakes, like
+// optional digits after mandatory ones, there's a suggestion of a valid
+// picture that was probably meant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PictureError {
+    position: usize,
+    character: Option<char>,
+    suggestion: Option<String>,
+}
+
+impl PictureError {
+    pub(crate) fn new(position: usize, character: Option<char>) -> Self {
+        Self {
+            position,
+            character,
+            suggestion: None,
+        }
+    }
+
+    pub(crate) fn with_suggestion(self, suggestion: String) -> Self {
+        Self {
+            suggestion: Some(suggestion),
+            ..self
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn character(&self) -> Option<char> {
+        self.character
+    }
+
+    // the whole picture as it was probably meant, as in "#,##0" for
+    // "0,##0#"
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref()
+    }
+
+    // the XPath error code for invalid pictures
+    pub fn code(&self) -> &'static str {
+        "FODF1310"
+    }
+
+    // the same error, with the position mapped, as when it was found by
+    // position in a part of the picture
+    pub(crate) fn map_position(self, f: impl FnOnce(usize) -> usize) -> Self {
+        Self {
+            position: f(self.position),
+            ..self
+        }
+    }
+}
+
+// What we document about an error, so that tools can explain it the way
+// rustc --explain does.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ErrorInfo {
+    variant: &'static str,
+    code: &'static str,
+    message: &'static str,
+    help: &'static str,
+}
+
+impl ErrorInfo {
+    // the name of the Error variant
+    pub fn variant(&self) -> &'static str {
+        self.variant
+    }
+
+    // the XPath error code the error is reported as; errors the
+    // specification has no code for are FOER0000
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    // a short message, where {} stands for what the variant carries
+    pub fn message(&self) -> &'static str {
+        self.message
+    }
+
+    // a longer explanation of what causes the error and how to avoid it
+    pub fn help(&self) -> &'static str {
+        self.help
+    }
+}
+
+// every variant of Error, in the order they're declared
+static ERRORS: [ErrorInfo; 10] = [
+    ErrorInfo {
+        variant: "InvalidPictureString",
+        code: "FODF1310",
+        message: "invalid picture string at {}",
+        help: "The picture doesn't follow the syntax of fn:format-integer. A \
+               decimal digit pattern needs at least one digit, its digits must \
+               all be from the same family, optional digits (#) can't follow \
+               mandatory ones, and grouping separators must be between digits, \
+               never next to each other or at the end.",
+    },
+    ErrorInfo {
+        variant: "InvalidFormatModifier",
+        code: "FODF1310",
+        message: "invalid format modifier at {}",
+        help: "The format modifier after the last semicolon of the picture \
+               must be c or o, for cardinal or ordinal numbers, optionally \
+               followed by a variation in parentheses, like o(-er), and then \
+               by a or t, for alphabetic or traditional numbering. Each part \
+               may be left out, but they must come in this order.",
+    },
+    ErrorInfo {
+        variant: "InvalidLanguageTag",
+        code: "FOER0000",
+        message: "invalid language tag {}",
+        help: "The language isn't a valid BCP 47 language tag, like \"en\" or \
+               \"de-CH\". The XPath functions ignore such languages instead of \
+               reporting them.",
+    },
+    ErrorInfo {
+        variant: "InvalidCountry",
+        code: "FOER0000",
+        message: "invalid country {}",
+        help: "The country isn't a valid region subtag, which is two ascii \
+               letters like \"GB\" or three digits like \"419\".",
+    },
+    ErrorInfo {
+        variant: "InvalidDigitFamily",
+        code: "FOER0000",
+        message: "{} is not a decimal digit",
+        help: "The digit family is given by any of its digits, which must be a \
+               character of Unicode category Nd, like \"0\" or \"٣\".",
+    },
+    ErrorInfo {
+        variant: "InvalidCurrencyCode",
+        code: "FOER0000",
+        message: "invalid currency code {}",
+        help: "Currency codes are ISO 4217 codes of three ascii letters, like \
+               \"EUR\".",
+    },
+    ErrorInfo {
+        variant: "ConflictingOptions",
+        code: "FOER0000",
+        message: "the options {} and {} can't be used together",
+        help: "Some options contradict each other; strict mode, for instance, \
+               sticks to the specification, so it can't be combined with \
+               extensions to it. Leave out one of the two.",
+    },
+    ErrorInfo {
+        variant: "InvalidValidityBitmap",
+        code: "FOER0000",
+        message: "the validity bitmap doesn't match the values",
+        help: "A column of values comes with a validity bitmap that has a bit \
+               for every value, so it must have at least as many bits as there \
+               are values.",
+    },
+    ErrorInfo {
+        variant: "OutputTooLong",
+        code: "FOER0000",
+        message: "the output is longer than the limit",
+        help: "The formatted value would be longer than the maximum output \
+               length set in the options. Raise the limit, or use a picture \
+               that produces shorter output, like digits instead of words.",
+    },
+    ErrorInfo {
+        variant: "Unsupported",
+        code: "FOER0000",
+        message: "unsupported numbering sequence {}",
+        help: "The picture is valid, but asks for something that isn't \
+               implemented, like a numbering sequence we don't know. This is \
+               only reported in strict mode; otherwise the value is formatted \
+               as the specification prescribes for that case, usually as a \
+               decimal number.",
+    },
+];
+
+impl Error {
+    // the same error, with the position of an invalid picture mapped
+    pub(crate) fn map_picture_position(self, f: impl FnOnce(usize) -> usize) -> Self {
+        match self {
+            Error::InvalidPictureString(error) => {
+                Error::InvalidPictureString(error.map_position(f))
+            }
+            Error::InvalidFormatModifier(error) => {
+                Error::InvalidFormatModifier(error.map_position(f))
+            }
+            error => error,
+        }
+    }
+
+    // the same error, with a suggestion attached if it's an invalid picture
+    // and there is one
+    pub(crate) fn suggest_picture(self, f: impl FnOnce() -> Option<String>) -> Self {
+        match self {
+            Error::InvalidPictureString(error) => match f() {
+                Some(suggestion) => Error::InvalidPictureString(error.with_suggestion(suggestion)),
+                None => Error::InvalidPictureString(error),
+            },
+            error => error,
+        }
+    }
+
+    // what we document about this error
+    pub fn info(&self) -> &'static ErrorInfo {
+        &ERRORS[match self {
+            Error::InvalidPictureString(_) => 0,
+            Error::InvalidFormatModifier(_) => 1,
+            Error::InvalidLanguageTag(_) => 2,
+            Error::InvalidCountry(_) => 3,
+            Error::InvalidDigitFamily(_) => 4,
+            Error::InvalidCurrencyCode(_) => 5,
+            Error::ConflictingOptions(..) => 6,
+            Error::InvalidValidityBitmap => 7,
+            Error::OutputTooLong => 8,
+            Error::Unsupported(_) => 9,
+        }]
+    }
+
+    // the message for the error in English
+    pub fn message(&self) -> String {
+        fill(self.info().message(), &self.message_arguments())
+    }
+
+    // what the error carries, in the order of the placeholders of its
+    // message
+    pub(crate) fn message_arguments(&self) -> Vec<String> {
+        match self {
+            Error::InvalidPictureString(error) | Error::InvalidFormatModifier(error) => {
+                vec![error.position().to_string()]
+            }
+            Error::InvalidLanguageTag(tag) => vec![tag.clone()],
+            Error::InvalidCountry(country) => vec![country.clone()],
+            Error::InvalidDigitFamily(digit) => vec![digit.to_string()],
+            Error::InvalidCurrencyCode(code) => vec![code.clone()],
+            Error::ConflictingOptions(first, second) => {
+                vec![first.to_string(), second.to_string()]
+            }
+            Error::InvalidValidityBitmap | Error::OutputTooLong => vec![],
+            Error::Unsupported(Feature::NumberingSequence(token)) => vec![token.clone()],
+        }
+    }
+
+    // the error as a JSON object, for editors and web frontends that show
+    // it to stylesheet authors, as in
+    // {"code":"FODF1310","variant":"InvalidPictureString","message":"...",
+    // "position":3,"character":",","suggestion":"0,000"}. Only invalid
+    // pictures and modifiers have a position; the character is null when
+    // the picture ends too soon, and the suggestion when we have none.
+    pub fn to_json(&self) -> String {
+        let info = self.info();
+        let mut json = String::from("{\"code\":");
+        write_json_string(info.code(), &mut json);
+        json.push_str(",\"variant\":");
+        write_json_string(info.variant(), &mut json);
+        json.push_str(",\"message\":");
+        write_json_string(&self.message(), &mut json);
+        if let Error::InvalidPictureString(error) | Error::InvalidFormatModifier(error) = self {
+            json.push_str(",\"position\":");
+            json.push_str(&error.position().to_string());
+            json.push_str(",\"character\":");
+            match error.character() {
+                Some(character) => write_json_string(character.encode_utf8(&mut [0; 4]), &mut json),
+                None => json.push_str("null"),
+            }
+            json.push_str(",\"suggestion\":");
+            match error.suggestion() {
+                Some(suggestion) => write_json_string(suggestion, &mut json),
+                None => json.push_str("null"),
+            }
+        }
+        json.push('}');
+        json
+    }
+
+    // what we document about every error
+    pub fn registry() -> &'static [ErrorInfo] {
+        &ERRORS
+    }
+
+    // what we document about the errors reported with the code
+    pub fn explain(code: &str) -> impl Iterator<Item = &'static ErrorInfo> + '_ {
+        ERRORS.iter().filter(move |info| info.code == code)
+    }
+}
+
+// The error code and the English message, and for invalid pictures the
+// character at the position, as in "FODF1310: invalid picture string at 1:
+// unexpected ','".
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.info().code(), self.message())?;
+        match self {
+            Error::InvalidPictureString(error) | Error::InvalidFormatModifier(error) => {
+                match error.character() {
+                    Some(c) => write!(f, ": unexpected {:?}", c),
+                    None => f.write_str(": unexpected end"),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+// the template with each {} replaced by the next argument. Placeholders
+// without an argument are left as they are.
+pub(crate) fn fill(template: &str, arguments: &[String]) -> String {
+    let mut message = String::with_capacity(template.len());
+    let mut arguments = arguments.iter();
+    let mut parts = template.split("{}");
+    if let Some(first) = parts.next() {
+        message.push_str(first);
+    }
+    for part in parts {
+        match arguments.next() {
+            Some(argument) => message.push_str(argument),
+            None => message.push_str("{}"),
+        }
+        message.push_str(part);
+    }
+    message
+}
+
+// append the text as a JSON string, with quotes
+fn write_json_string(text: &str, json: &mut String) {
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c < ' ' => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_info() {
+        let errors = [
+            Error::InvalidPictureString(PictureError::new(0, None)),
+            Error::InvalidFormatModifier(PictureError::new(0, None)),
+            Error::InvalidLanguageTag("x".to_string()),
+            Error::InvalidCountry("x".to_string()),
+            Error::InvalidDigitFamily('x'),
+            Error::InvalidCurrencyCode("x".to_string()),
+            Error::ConflictingOptions("strict", "extensions"),
+            Error::InvalidValidityBitmap,
+            Error::OutputTooLong,
+            Error::Unsupported(Feature::NumberingSequence("x".to_string())),
+        ];
+        assert_eq!(errors.len(), Error::registry().len());
+        for error in errors {
+            let info = error.info();
+            assert!(format!("{:?}", error).starts_with(info.variant()));
+            assert!(!info.message().is_empty());
+            assert!(!info.help().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let explained: Vec<_> = Error::explain("FODF1310").collect();
+        assert_eq!(
+            explained,
+            [
+                Error::InvalidPictureString(PictureError::new(0, None)).info(),
+                Error::InvalidFormatModifier(PictureError::new(0, None)).info()
+            ]
+        );
+        assert_eq!(Error::explain("FOER0000").count(), 8);
+        assert_eq!(Error::explain("XPST0003").count(), 0);
+    }
+
+    #[test]
+    fn test_message() {
+        assert_eq!(
+            Error::ConflictingOptions("strict", "extensions").message(),
+            "the options strict and extensions can't be used together"
+        );
+        assert_eq!(
+            Error::OutputTooLong.message(),
+            "the output is longer than the limit"
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let error = Error::InvalidPictureString(PictureError::new(1, Some(',')));
+        assert_eq!(
+            error.to_string(),
+            "FODF1310: invalid picture string at 1: unexpected ','"
+        );
+        let error = Error::InvalidFormatModifier(PictureError::new(4, None));
+        assert_eq!(
+            error.to_string(),
+            "FODF1310: invalid format modifier at 4: unexpected end"
+        );
+        assert_eq!(
+            Error::InvalidDigitFamily('x').to_string(),
+            "FOER0000: x is not a decimal digit"
+        );
+    }
+
+    #[test]
+    fn test_std_error() {
+        fn parse() -> Result<(), Box<dyn std::error::Error>> {
+            crate::format_integer::Picture::parse("0,")?;
+            Ok(())
+        }
+        let error = parse().unwrap_err();
+        assert!(error.to_string().starts_with("FODF1310: "));
+        assert!(error.downcast_ref::<Error>().is_some());
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(fill("{} and {}", &["a".to_string()]), "a and {}");
+        assert_eq!(fill("none", &[]), "none");
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(
+            Error::InvalidPictureString(PictureError::new(3, Some('"'))).to_json(),
+            r#"{"code":"FODF1310","variant":"InvalidPictureString","message":"invalid picture string at 3","position":3,"character":"\"","suggestion":null}"#
+        );
+        assert_eq!(
+            Error::InvalidPictureString(PictureError::new(4, None)).to_json(),
+            r#"{"code":"FODF1310","variant":"InvalidPictureString","message":"invalid picture string at 4","position":4,"character":null,"suggestion":null}"#
+        );
+        assert_eq!(
+            Error::InvalidPictureString(
+                PictureError::new(1, Some(',')).with_suggestion("0".to_string())
+            )
+            .to_json(),
+            r#"{"code":"FODF1310","variant":"InvalidPictureString","message":"invalid picture string at 1","position":1,"character":",","suggestion":"0"}"#
+        );
+        assert_eq!(
+            Error::InvalidLanguageTag("a\\b\n".to_string()).to_json(),
+            r#"{"code":"FOER0000","variant":"InvalidLanguageTag","message":"invalid language tag a\\b\n"}"#
+        );
+    }
+
+    #[test]
+    fn test_to_json_control_characters() {
+        let mut json = String::new();
+        write_json_string("\u{1}", &mut json);
+        assert_eq!(json, r#""\u0001""#);
+    }
+}
@@ -0,0 +1,101 @@
+// Ethiopic numerals, for the "፩" format token. These aren't positional:
+// the digits are grouped in pairs from the right, each pair is written
+// with letters for its tens and units, and the pairs are separated by
+// signs for a hundred (፻) and ten thousand (፼), so "፲፪፼፴፬፻፶፮" is 12 ×
+// 10,000 + 34 × 100 + 56. A pair that is just one isn't written before a
+// sign, as in "፻" (100). There's no zero, so it's formatted as a decimal
+// number instead.
+
+const UNITS: [char; 9] = ['፩', '፪', '፫', '፬', '፭', '፮', '፯', '፰', '፱'];
+const TENS: [char; 9] = ['፲', '፳', '፴', '፵', '፶', '፷', '፸', '፹', '፺'];
+const HUNDRED: char = '፻';
+const TEN_THOUSAND: char = '፼';
+
+// the maximum length in bytes of the numeral for a number with the given
+// amount of digits: every pair takes at most two letters and a sign, each
+// taking three bytes
+pub(crate) const fn max_len(digit_count: usize) -> usize {
+    digit_count.div_ceil(2).saturating_mul(9)
+}
+
+// append the numeral for the value given as ascii digits, or return false
+// if the value is zero as there is no numeral for it
+pub(crate) fn write(digits: &str, output: &mut String) -> bool {
+    let digits = digits.trim_start_matches('0').as_bytes();
+    if digits.is_empty() {
+        return false;
+    }
+    let pair_count = digits.len().div_ceil(2);
+    // the first pair may be a single digit
+    let mut start = 0;
+    let mut end = digits.len() - (pair_count - 1) * 2;
+    for index in (0..pair_count).rev() {
+        let pair = &digits[start..end];
+        let (tens, units) = match pair {
+            [tens, units] => (tens - b'0', units - b'0'),
+            [units] => (0, units - b'0'),
+            _ => (0, 0),
+        };
+        if (tens, units) != (0, 1) || index == 0 {
+            if tens > 0 {
+                output.push(TENS[usize::from(tens) - 1]);
+            }
+            if units > 0 {
+                output.push(UNITS[usize::from(units) - 1]);
+            }
+        }
+        let is_zero = (tens, units) == (0, 0);
+        if index % 2 == 1 && !is_zero {
+            output.push(HUNDRED);
+        } else if index % 2 == 0 && index > 0 {
+            // every ten thousand is written, as those of the pairs on the
+            // left multiply
+            output.push(TEN_THOUSAND);
+        }
+        start = end;
+        end += 2;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ethiopic(digits: &str) -> Option<String> {
+        let mut output = String::new();
+        write(digits, &mut output).then_some(output)
+    }
+
+    #[test]
+    fn test_write() {
+        assert_eq!(ethiopic("1").unwrap(), "፩");
+        assert_eq!(ethiopic("10").unwrap(), "፲");
+        assert_eq!(ethiopic("99").unwrap(), "፺፱");
+        assert_eq!(ethiopic("100").unwrap(), "፻");
+        assert_eq!(ethiopic("101").unwrap(), "፻፩");
+        assert_eq!(ethiopic("250").unwrap(), "፪፻፶");
+        assert_eq!(ethiopic("10000").unwrap(), "፼");
+        assert_eq!(ethiopic("10100").unwrap(), "፼፻");
+        assert_eq!(ethiopic("123456").unwrap(), "፲፪፼፴፬፻፶፮");
+    }
+
+    #[test]
+    fn test_large() {
+        assert_eq!(ethiopic("1000000").unwrap(), "፻፼");
+        assert_eq!(ethiopic("100000000").unwrap(), "፼፼");
+        assert_eq!(ethiopic("12345678").unwrap(), "፲፪፻፴፬፼፶፮፻፸፰");
+    }
+
+    #[test]
+    fn test_zero() {
+        assert_eq!(ethiopic("0"), None);
+    }
+
+    #[test]
+    fn test_max_len() {
+        for digits in ["9", "99", "999", "9999", "99999999999", "1010101"] {
+            assert!(ethiopic(digits).unwrap().len() <= max_len(digits.len()));
+        }
+    }
+}
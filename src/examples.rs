@@ -0,0 +1,79 @@
+// Examples of the behavior the XPath specification requires, as data, so
+// that they can be run as tests here and reused by downstream test suites.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegerExample {
+    pub value: i64,
+    pub picture: &'static str,
+    // the language to format in, for the examples that depend on it; the
+    // others are formatted without one
+    pub language: Option<&'static str>,
+    pub expected: &'static str,
+}
+
+const fn example(value: i64, picture: &'static str, expected: &'static str) -> IntegerExample {
+    IntegerExample {
+        value,
+        picture,
+        language: None,
+        expected,
+    }
+}
+
+const fn example_lang(
+    value: i64,
+    picture: &'static str,
+    language: &'static str,
+    expected: &'static str,
+) -> IntegerExample {
+    IntegerExample {
+        value,
+        picture,
+        language: Some(language),
+        expected,
+    }
+}
+
+// Examples from the specification of fn:format-integer, both from its list
+// of examples and from the description of decimal digit patterns.
+pub const FORMAT_INTEGER: &[IntegerExample] = &[
+    example(123, "0000", "0123"),
+    example(123, "w", "one hundred and twenty-three"),
+    example_lang(21, "1;o", "en", "21st"),
+    example_lang(14, "Ww;o(-e)", "de", "Vierzehnte"),
+    example(7, "a", "g"),
+    example(57, "I", "LVII"),
+    example(1234, "#;##0;", "1;234"),
+    // a format token of 1 generates the sequence 1, 2, ... 10, 11, 12
+    example(1, "1", "1"),
+    example(12, "1", "12"),
+    // a format token of 01 (or equivalently 00 or 99) generates 01, 02, ...
+    // 99, 100, 101
+    example(1, "01", "01"),
+    example(12, "01", "12"),
+    example(101, "01", "101"),
+    example(1, "00", "01"),
+    example(1, "99", "01"),
+    // a format token of ١ (arabic-indic digit one) generates ١, ٢, ٣ ...
+    example(3, "١", "٣"),
+    example(12, "١", "١٢"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_integer::{format_integer, format_integer_lang};
+
+    #[test]
+    fn test_format_integer_examples() {
+        for example in FORMAT_INTEGER {
+            let formatted = match example.language {
+                Some(language) => {
+                    format_integer_lang(example.value.into(), example.picture, language)
+                }
+                None => format_integer(example.value.into(), example.picture),
+            };
+            assert_eq!(formatted.unwrap(), example.expected, "{:?}", example);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+// A 64-bit FNV-1a hash. Unlike std's hashers this is guaranteed to produce
+// the same result across runs, platforms and Rust versions, so fingerprints
+// can be used as keys in persistent caches.
+pub(crate) struct Fingerprinter(u64);
+
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl Fingerprinter {
+    pub(crate) fn new() -> Self {
+        Self(OFFSET_BASIS)
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(PRIME);
+        }
+    }
+
+    pub(crate) fn write_u8(&mut self, n: u8) {
+        self.write(&[n]);
+    }
+
+    // usize is written as 64 bits so the result doesn't depend on the
+    // platform
+    pub(crate) fn write_usize(&mut self, n: usize) {
+        self.write(&(n as u64).to_le_bytes());
+    }
+
+    pub(crate) fn write_char(&mut self, c: char) {
+        self.write(&(c as u32).to_le_bytes());
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a_known_values() {
+        // reference values for 64-bit FNV-1a
+        assert_eq!(Fingerprinter::new().finish(), 0xcbf29ce484222325);
+        let mut f = Fingerprinter::new();
+        f.write(b"a");
+        assert_eq!(f.finish(), 0xaf63dc4c8601ec8c);
+        let mut f = Fingerprinter::new();
+        f.write(b"foobar");
+        assert_eq!(f.finish(), 0x85944171f73967e8);
+    }
+}
@@ -1,6 +1,24 @@
-use crate::digit::{is_group_separator, AsciiDigit, DigitFamily};
+use crate::alphabetic::Alphabet;
+use crate::casing::Casing;
+use crate::circled;
+use crate::cjk::{self, CjkNumerals};
+use crate::digit::{AsciiDigit, DigitFamily};
+use crate::error::{Error, Feature, PictureError};
+use crate::ethiopic;
+use crate::fingerprint::Fingerprinter;
+use crate::grouping::Grouping;
+use crate::info::{GroupingInfo, PictureInfo, TokenKind};
+use crate::integer::{with_digits, Integer};
+use crate::options::FormatOptions;
+use crate::roman;
+use crate::sink::{ChunkKind, FormatSink, WriteSink};
+use crate::tokens::{CharClass, Symbols};
+use crate::traditional::TraditionalSystem;
+use crate::words::{self, Spelling};
 use ibig::IBig;
-use num_traits::Signed;
+use icu::locid::LanguageIdentifier;
+use icu::normalizer::ComposingNormalizer;
+use std::fmt;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 enum Sign {
@@ -9,404 +27,2675 @@ enum Sign {
     GroupSeparator(char),
 }
 
+impl Sign {
+    fn fingerprint(&self, f: &mut Fingerprinter) {
+        match self {
+            Sign::OptionalDigit => f.write_u8(0),
+            Sign::MandatoryDigit => f.write_u8(1),
+            Sign::GroupSeparator(c) => {
+                f.write_u8(2);
+                f.write_char(*c);
+            }
+        }
+    }
+}
+
+// The rules for decimal digit patterns, as a state machine over the signs.
+// The spec (fn:format-integer, 4.6.1) requires that:
+//
+// - there is at least one mandatory digit sign
+// - optional digit signs don't follow mandatory digit signs
+// - grouping separators aren't at the start or the end, and aren't
+//   adjacent to each other
+//
+// So an optional digit may be followed by a separator, but only if a digit
+// follows that in turn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValidationState {
+    Start,
+    OptionalDigit,
+    SeparatorAfterOptional,
+    MandatoryDigit,
+    SeparatorAfterMandatory,
+}
+
+impl ValidationState {
+    // the state after the given sign, or None if the sign is not allowed
+    fn next(self, sign: &Sign) -> Option<Self> {
+        use ValidationState::*;
+        match (self, sign) {
+            (Start, Sign::OptionalDigit) => Some(OptionalDigit),
+            (Start, Sign::MandatoryDigit) => Some(MandatoryDigit),
+            (Start, Sign::GroupSeparator(_)) => None,
+
+            (OptionalDigit, Sign::OptionalDigit) => Some(OptionalDigit),
+            (OptionalDigit, Sign::MandatoryDigit) => Some(MandatoryDigit),
+            (OptionalDigit, Sign::GroupSeparator(_)) => Some(SeparatorAfterOptional),
+
+            (SeparatorAfterOptional, Sign::OptionalDigit) => Some(OptionalDigit),
+            (SeparatorAfterOptional, Sign::MandatoryDigit) => Some(MandatoryDigit),
+            (SeparatorAfterOptional, Sign::GroupSeparator(_)) => None,
+
+            (MandatoryDigit, Sign::OptionalDigit) => None,
+            (MandatoryDigit, Sign::MandatoryDigit) => Some(MandatoryDigit),
+            (MandatoryDigit, Sign::GroupSeparator(_)) => Some(SeparatorAfterMandatory),
+
+            (SeparatorAfterMandatory, Sign::OptionalDigit) => None,
+            (SeparatorAfterMandatory, Sign::MandatoryDigit) => Some(MandatoryDigit),
+            (SeparatorAfterMandatory, Sign::GroupSeparator(_)) => None,
+        }
+    }
+
+    // a pattern can only end in a mandatory digit
+    fn is_accepting(self) -> bool {
+        self == ValidationState::MandatoryDigit
+    }
+}
+
 #[derive(Debug, PartialEq)]
-struct NonRegular {
+struct Pattern {
+    // the signs, which the fingerprint of irregular patterns is made of
     signs: Vec<Sign>,
+    grouping: Grouping,
     mandatory_digit_max: usize,
     digit_family: Option<DigitFamily>,
 }
 
-impl NonRegular {
-    fn new(signs: Vec<Sign>, digit_family: Option<DigitFamily>) -> Self {
-        Self {
+impl Pattern {
+    // errors are reported by the index of the character in the pattern
+    fn new(pattern: &[char]) -> Result<Self, Error> {
+        let (signs, digit_family) = Self::parse(pattern)?;
+        Self::validate(pattern, &signs)?;
+
+        let grouping = Grouping::from_signs(signs.iter().map(|sign| match sign {
+            Sign::GroupSeparator(separator) => Some(*separator),
+            Sign::OptionalDigit | Sign::MandatoryDigit => None,
+        }));
+        Ok(Self {
             mandatory_digit_max: signs
                 .iter()
-                .filter(|s| matches!(s, Sign::MandatoryDigit))
+                .filter(|sign| matches!(sign, Sign::MandatoryDigit))
                 .count(),
             signs,
+            grouping,
             digit_family,
-        }
+        })
     }
 
-    fn signs(&self) -> impl Iterator<Item = Sign> + '_ {
-        self.signs
+    fn parse(pattern: &[char]) -> Result<(Vec<Sign>, Option<DigitFamily>), Error> {
+        let mut digit_family = None;
+
+        let signs: Result<Vec<Sign>, Error> = pattern
             .iter()
             .copied()
-            .rev()
-            .chain(std::iter::repeat(Sign::OptionalDigit))
+            .enumerate()
+            .map(|(index, c)| match Symbols::INTEGER.classify(c) {
+                CharClass::OptionalDigit => Ok(Sign::OptionalDigit),
+                CharClass::GroupSeparator => Ok(Sign::GroupSeparator(c)),
+                CharClass::MandatoryDigit(found_digit_family) => {
+                    if let Some(digit_family) = digit_family {
+                        if found_digit_family != digit_family {
+                            return Err(invalid_at(index, Some(c)));
+                        }
+                    } else {
+                        digit_family = Some(found_digit_family);
+                    }
+                    Ok(Sign::MandatoryDigit)
+                }
+                CharClass::Passive => Err(invalid_at(index, Some(c))),
+            })
+            .collect();
+        Ok((signs?, digit_family))
+    }
+
+    fn validate(pattern: &[char], signs: &[Sign]) -> Result<(), Error> {
+        let mut state = ValidationState::Start;
+        for (index, sign) in signs.iter().enumerate() {
+            state = state
+                .next(sign)
+                .ok_or_else(|| invalid_at(index, Some(pattern[index])))?;
+        }
+        if state.is_accepting() {
+            Ok(())
+        } else {
+            // the pattern ends in the wrong place, which we report at its
+            // last character, if it has one
+            let last = pattern.len().saturating_sub(1);
+            Err(invalid_at(last, pattern.last().copied()))
+        }
+    }
+
+    fn separator_at(&self, digits_to_the_right: usize) -> Option<char> {
+        self.grouping.separator_at(digits_to_the_right)
+    }
+
+    const fn separators_len(&self, total: usize) -> usize {
+        self.grouping.separators_len(total)
     }
 
-    fn mandatory_digit_max(&self) -> usize {
+    const fn mandatory_digit_max(&self) -> usize {
         self.mandatory_digit_max
     }
+
+    const fn digit_family(&self) -> Option<DigitFamily> {
+        self.digit_family
+    }
+
+    fn fingerprint(&self, f: &mut Fingerprinter) {
+        match &self.grouping {
+            Grouping::Irregular(_) => {
+                f.write_u8(0);
+                f.write_usize(self.signs.len());
+                for sign in &self.signs {
+                    sign.fingerprint(f);
+                }
+            }
+            Grouping::Regular { separator, size } => {
+                f.write_u8(1);
+                f.write_char(*separator);
+                f.write_usize(*size);
+                f.write_usize(self.mandatory_digit_max);
+            }
+        }
+        match self.digit_family() {
+            Some(digit_family) => {
+                f.write_u8(1);
+                f.write_char(digit_family.zero());
+            }
+            None => f.write_u8(0),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
-struct Regular {
-    group_separator: char,
-    count: usize,
-    mandatory_digit_max: usize,
-    digit_family: Option<DigitFamily>,
+enum PrimaryToken {
+    DecimalDigitPattern(Pattern),
+    // "w", "W" or "Ww", the number in words, and whether the traditional
+    // modifier was given
+    Words(Casing, bool),
+    // "i" or "I", Roman numerals
+    Roman(Casing),
+    // "a" or "A", letters, or the first letter of another alphabet
+    Alphabetic(Alphabet, Casing),
+    // "א", "Ա" or "ა", letters that add up to the value, or "௧" with the
+    // traditional modifier
+    Traditional(TraditionalSystem),
+    // "①", circled numbers
+    Circled,
+    // "፩", Ethiopic numerals
+    Ethiopic,
+    // "一" or "壹", CJK numerals
+    Cjk(CjkNumerals),
 }
 
-impl Regular {
-    fn signs(&self) -> impl Iterator<Item = Sign> + '_ {
-        RegularIterator::new(self.group_separator, self.count)
+impl PrimaryToken {
+    fn parse(token: &[char], traditional: bool, options: &FormatOptions) -> Result<Self, Error> {
+        // Tamil digits are decimal digits unless traditional numbering is
+        // asked for
+        if let (['௧'], true) = (token, traditional) {
+            return Ok(Self::Traditional(TraditionalSystem::Tamil));
+        }
+        if is_decimal_digit_pattern(token) {
+            return Ok(Self::DecimalDigitPattern(Pattern::new(token)?));
+        }
+        match token {
+            ['w'] => return Ok(Self::Words(Casing::Lower, traditional)),
+            ['W'] => return Ok(Self::Words(Casing::Upper, traditional)),
+            ['W', 'w'] => return Ok(Self::Words(Casing::Title, traditional)),
+            ['i'] => return Ok(Self::Roman(Casing::Lower)),
+            ['I'] => return Ok(Self::Roman(Casing::Upper)),
+            ['①'] => return Ok(Self::Circled),
+            ['፩'] => return Ok(Self::Ethiopic),
+            [first] => {
+                if let Some((alphabet, casing)) = Alphabet::new(*first) {
+                    return Ok(Self::Alphabetic(alphabet, casing));
+                }
+                if let Some(numerals) = CjkNumerals::new(*first) {
+                    return Ok(Self::Cjk(numerals));
+                }
+                if let Some(system) = TraditionalSystem::new(*first) {
+                    return Ok(Self::Traditional(system));
+                }
+            }
+            _ => {}
+        }
+        // any other token is a numbering sequence we don't support; the
+        // specification says to use "1" instead
+        if options.strict() {
+            return Err(Error::Unsupported(Feature::NumberingSequence(
+                token.iter().collect(),
+            )));
+        }
+        Ok(Self::DecimalDigitPattern(Pattern::new(&['1'])?))
     }
 
-    fn mandatory_digit_max(&self) -> usize {
-        self.mandatory_digit_max
+    // append the numeral for the ascii digits of an absolute value, and
+    // return whether there is one; decimal digit patterns and words are
+    // formatted elsewhere
+    fn write_numeral(&self, digits: &str, options: &FormatOptions, output: &mut String) -> bool {
+        match self {
+            PrimaryToken::DecimalDigitPattern(_) | PrimaryToken::Words(..) => false,
+            PrimaryToken::Roman(casing) => match roman::value(digits, options.roman_overflow()) {
+                Some(value) => {
+                    roman::write(
+                        value,
+                        options.roman_overflow(),
+                        options.roman_number_forms(),
+                        output,
+                    );
+                    // numerals aren't in any language, so the language
+                    // doesn't decide their casing
+                    casing.apply(output, &LanguageIdentifier::UND);
+                    true
+                }
+                None => false,
+            },
+            PrimaryToken::Alphabetic(alphabet, casing) => {
+                let written = alphabet.write(digits, output);
+                casing.apply(output, &LanguageIdentifier::UND);
+                written
+            }
+            PrimaryToken::Traditional(system) => {
+                let numerals = system.numerals();
+                match numerals.value(digits) {
+                    Some(value) => {
+                        numerals.write(value, output);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            PrimaryToken::Ethiopic => ethiopic::write(digits, output),
+            PrimaryToken::Circled => match circled::circled(digits) {
+                Some(c) => {
+                    output.push(c);
+                    true
+                }
+                None => false,
+            },
+            PrimaryToken::Cjk(numerals) => numerals.write(digits, output),
+        }
     }
 }
 
-struct RegularIterator {
-    position: usize,
-    group_separator: char,
-    grouping_size: usize,
+// a token with a digit in it is a decimal digit pattern. We count '#' too,
+// so that a pattern without mandatory digits is reported as invalid. The
+// empty token is invalid as well.
+pub(crate) fn is_decimal_digit_pattern(token: &[char]) -> bool {
+    token.is_empty()
+        || token
+            .iter()
+            .any(|c| Symbols::INTEGER.classify(*c).is_digit())
+}
+
+// An extension: a '+' or '-' at the start or the end of a decimal digit
+// pattern says where the sign goes, and with '+' positive numbers get a
+// sign too. Without extensions, these would be grouping separators, which
+// aren't allowed there.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct SignPlaceholder {
+    trailing: bool,
+    always: bool,
 }
 
-impl RegularIterator {
-    fn new(group_separator: char, grouping_size: usize) -> Self {
-        Self {
-            position: 0,
-            grouping_size,
-            group_separator,
+impl SignPlaceholder {
+    // split the placeholder off the token, if there is one
+    fn parse(token: &[char]) -> (&[char], Self) {
+        let placeholder = |c: char| match c {
+            '+' => Some(true),
+            '-' => Some(false),
+            _ => None,
+        };
+        match token {
+            [first, rest @ ..] if is_decimal_digit_pattern(rest) => {
+                if let Some(always) = placeholder(*first) {
+                    return (
+                        rest,
+                        Self {
+                            trailing: false,
+                            always,
+                        },
+                    );
+                }
+            }
+            _ => {}
+        }
+        match token {
+            [rest @ .., last] if is_decimal_digit_pattern(rest) => {
+                if let Some(always) = placeholder(*last) {
+                    return (
+                        rest,
+                        Self {
+                            trailing: true,
+                            always,
+                        },
+                    );
+                }
+            }
+            _ => {}
         }
+        (token, Self::default())
+    }
+
+    fn fingerprint(&self, f: &mut Fingerprinter) {
+        f.write_u8(self.trailing as u8);
+        f.write_u8(self.always as u8);
     }
 }
 
-impl Iterator for RegularIterator {
-    type Item = Sign;
+// An extension: a '¤' at the start or the end of a decimal digit pattern
+// stands for the currency symbol of the options, and '¤¤' for the ISO
+// currency code, as in CLDR patterns. A space between the placeholder and
+// the digits is kept. The currency goes between the sign and the digits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CurrencyPlaceholder {
+    // what we write, including the space, if any
+    text: String,
+    trailing: bool,
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.position < self.grouping_size {
-            self.position += 1;
-            Some(Sign::MandatoryDigit)
+impl CurrencyPlaceholder {
+    // split the placeholder off the token, if there is one
+    fn parse<'a>(token: &'a [char], options: &FormatOptions) -> (&'a [char], Option<Self>) {
+        let is_space = |c: &char| matches!(c, ' ' | '\u{a0}');
+        let (rest, placeholder, trailing) = match token {
+            ['¤', '¤', rest @ ..] => (rest, "¤¤", false),
+            ['¤', rest @ ..] => (rest, "¤", false),
+            [rest @ .., '¤', '¤'] => (rest, "¤¤", true),
+            [rest @ .., '¤'] => (rest, "¤", true),
+            _ => return (token, None),
+        };
+        let (rest, space) = match (rest, trailing) {
+            ([space, rest @ ..], false) if is_space(space) => (rest, Some(*space)),
+            ([rest @ .., space], true) if is_space(space) => (rest, Some(*space)),
+            _ => (rest, None),
+        };
+        if !is_decimal_digit_pattern(rest) {
+            return (token, None);
+        }
+        // without the currency we asked for we fall back to the other one,
+        // and without either we leave the placeholder
+        let currency = if placeholder == "¤" {
+            options.currency_symbol().or(options.currency_code())
         } else {
-            self.position = 0;
-            Some(Sign::GroupSeparator(self.group_separator))
+            options.currency_code().or(options.currency_symbol())
+        };
+        let currency = currency.unwrap_or(placeholder);
+        let text = match (space, trailing) {
+            (Some(space), false) => format!("{}{}", currency, space),
+            (Some(space), true) => format!("{}{}", space, currency),
+            (None, _) => currency.to_string(),
+        };
+        (rest, Some(Self { text, trailing }))
+    }
+
+    fn fingerprint(&self, f: &mut Fingerprinter) {
+        f.write_u8(self.trailing as u8);
+        f.write_usize(self.text.len());
+        f.write(self.text.as_bytes());
+    }
+}
+
+// An extension: each grouping separator at the end of a decimal digit
+// pattern divides the value by a thousand, as in Java's DecimalFormat, so
+// that "#,##0," formats values in thousands. This comes before any sign
+// or currency placeholder.
+fn parse_scale(mut token: &[char]) -> (&[char], usize) {
+    let mut scale = 0;
+    while let [rest @ .., last] = token {
+        // '+' and '-' are sign placeholders rather than separators here
+        let is_separator = Symbols::INTEGER.classify(*last) == CharClass::GroupSeparator
+            && !matches!(last, '+' | '-');
+        if !is_separator || !is_decimal_digit_pattern(rest) {
+            break;
+        }
+        token = rest;
+        scale += 1;
+    }
+    (token, scale)
+}
+
+// a valid pattern close to an invalid one, for the common mistakes:
+// optional digits after mandatory ones, which we move to the front, a
+// pattern without mandatory digits, where the last one becomes mandatory,
+// and separators at the ends or next to each other, which we drop. There's
+// none for patterns with other problems, like characters that aren't
+// digits.
+fn suggest_pattern(pattern: &[char]) -> Option<Vec<char>> {
+    if !is_decimal_digit_pattern(pattern) {
+        return None;
+    }
+    let (signs, digit_family) = Pattern::parse(pattern).ok()?;
+    let mut mandatory: Vec<char> = pattern
+        .iter()
+        .zip(&signs)
+        .filter(|(_, sign)| **sign == Sign::MandatoryDigit)
+        .map(|(c, _)| *c)
+        .collect();
+    let mut optional = signs.len() - mandatory.len();
+    optional -= signs
+        .iter()
+        .filter(|sign| matches!(sign, Sign::GroupSeparator(_)))
+        .count();
+    if mandatory.is_empty() {
+        optional = optional.checked_sub(1)?;
+        mandatory.push(digit_family.map_or('0', |family| family.zero()));
+    }
+    let mut mandatory = mandatory.into_iter();
+    let mut suggestion = Vec::with_capacity(pattern.len());
+    // whether we just wrote a digit, so separators only go between digits
+    let mut after_digit = false;
+    for sign in &signs {
+        match sign {
+            Sign::GroupSeparator(separator) => {
+                if after_digit {
+                    suggestion.push(*separator);
+                    after_digit = false;
+                }
+            }
+            Sign::OptionalDigit | Sign::MandatoryDigit => {
+                if optional > 0 {
+                    optional -= 1;
+                    suggestion.push('#');
+                } else {
+                    suggestion.push(mandatory.next()?);
+                }
+                after_digit = true;
+            }
+        }
+    }
+    if !after_digit {
+        suggestion.pop();
+    }
+    (suggestion != pattern && Pattern::new(&suggestion).is_ok()).then_some(suggestion)
+}
+
+// the error for an invalid picture at the index, which the callers map to
+// a position in the picture
+fn invalid_at(index: usize, character: Option<char>) -> Error {
+    Error::InvalidPictureString(PictureError::new(index, character))
+}
+
+// the error for an invalid format modifier at the index
+fn invalid_modifier_at(index: usize, character: Option<char>) -> Error {
+    Error::InvalidFormatModifier(PictureError::new(index, character))
+}
+
+// put the output from start on in Unicode normalization form C. It almost
+// always is already, so we only allocate if it isn't.
+pub(crate) fn nfc_from(output: &mut String, start: usize) {
+    let normalizer = ComposingNormalizer::new_nfc();
+    if !normalizer.is_normalized(&output[start..]) {
+        let normalized = normalizer.normalize(&output[start..]);
+        output.truncate(start);
+        output.push_str(&normalized);
+    }
+}
+
+// divide a number given as ascii digits by a thousand scale times,
+// rounding half to even like DecimalFormat does
+fn scale_digits(s: &str, scale: usize) -> String {
+    let dropped_len = scale.saturating_mul(3).min(s.len());
+    let (kept, dropped) = s.split_at(s.len() - dropped_len);
+    // if we drop more digits than there are, the value is below half of
+    // what we divide by, so it becomes zero
+    let round_up = scale.saturating_mul(3) == dropped_len
+        && match dropped.as_bytes() {
+            [first, rest @ ..] if *first == b'5' => {
+                rest.iter().any(|d| *d != b'0')
+                    || kept.bytes().last().is_some_and(|d| (d - b'0') % 2 == 1)
+            }
+            [first, ..] => *first > b'5',
+            [] => false,
+        };
+    let mut digits: Vec<u8> = kept.bytes().collect();
+    if round_up {
+        let mut carry = true;
+        for digit in digits.iter_mut().rev() {
+            if *digit == b'9' {
+                *digit = b'0';
+            } else {
+                *digit += 1;
+                carry = false;
+                break;
+            }
+        }
+        if carry {
+            digits.insert(0, b'1');
         }
     }
+    let digits: String = digits.iter().map(|d| *d as char).collect();
+    match digits.trim_start_matches('0') {
+        "" => "0".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+// whether to produce cardinal numbers (1, 2, 3) or ordinal numbers (1st,
+// 2nd, 3rd), as given by the format modifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Numbering {
+    Cardinal,
+    Ordinal,
 }
 
+// what the format modifier after the last semicolon asks for
 #[derive(Debug, PartialEq)]
-enum Pattern {
-    NonRegular(NonRegular),
-    Regular(Regular),
+struct Modifier {
+    numbering: Numbering,
+    // the string in parentheses after c or o, which picks a variation of
+    // the numbers, like "-er" for German ordinals
+    variation: Option<String>,
+    // whether traditional numbering is asked for, which only matters for
+    // Tamil and Korean
+    traditional: bool,
 }
 
-impl Pattern {
-    fn new(pattern: &str) -> Result<Self, Error> {
-        let (signs, digit_family) = Self::parse(pattern)?;
-        Self::validate(&signs)?;
+impl Modifier {
+    const DEFAULT: Modifier = Modifier {
+        numbering: Numbering::Cardinal,
+        variation: None,
+        traditional: false,
+    };
+
+    // the format modifier is c or o for cardinal or ordinal, optionally
+    // with a variation in parentheses, followed by a or t for alphabetic
+    // or traditional numbering. We accept a and otherwise ignore it. This
+    // is the format-modifier production of the specification,
+    // ^([co](\(.+\))?)?[at]?$, where the variation can have parentheses
+    // of its own: it runs to the last closing parenthesis. Errors are
+    // reported by the index of the character in the modifier, apart from
+    // those in the primary format token.
+    fn parse(modifier: &[char]) -> Result<Self, Error> {
+        let (numbering, rest) = match modifier {
+            ['c', rest @ ..] => (Numbering::Cardinal, rest),
+            ['o', rest @ ..] => (Numbering::Ordinal, rest),
+            rest => return Self::parse_numbering(Numbering::Cardinal, None, rest, 0),
+        };
+        let (variation, rest) = match rest {
+            ['(', rest @ ..] => {
+                let end = rest
+                    .iter()
+                    .rposition(|c| *c == ')')
+                    .ok_or_else(|| invalid_modifier_at(1, Some('(')))?;
+                if end == 0 {
+                    return Err(invalid_modifier_at(2, Some(')')));
+                }
+                (Some(rest[..end].iter().collect()), &rest[end + 1..])
+            }
+            rest => (None, rest),
+        };
+        Self::parse_numbering(numbering, variation, rest, modifier.len() - rest.len())
+    }
+
+    // parse the rest of the modifier, which starts at the index
+    fn parse_numbering(
+        numbering: Numbering,
+        variation: Option<String>,
+        rest: &[char],
+        index: usize,
+    ) -> Result<Self, Error> {
+        let traditional = match rest {
+            [] | ['a'] => false,
+            ['t'] => true,
+            ['a' | 't', next, ..] => return Err(invalid_modifier_at(index + 1, Some(*next))),
+            [first, ..] => return Err(invalid_modifier_at(index, Some(*first))),
+        };
+        Ok(Self {
+            numbering,
+            variation,
+            traditional,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Picture {
+    // the picture as given, which is what we display
+    source: String,
+    // the options the picture was parsed with, which it formats with
+    // unless it's given others
+    options: FormatOptions,
+    token: PrimaryToken,
+    numbering: Numbering,
+    // the variation of the format modifier, if any
+    variation: Option<String>,
+    sign: SignPlaceholder,
+    // the value is divided by a thousand this many times
+    scale: usize,
+    currency: Option<CurrencyPlaceholder>,
+}
+
+impl Picture {
+    pub fn parse(picture: &str) -> Result<Self, Error> {
+        Self::parse_chars(picture.char_indices())
+    }
+
+    // parse a picture from the characters along with their positions, for
+    // hosts that already have the picture in some other form and don't
+    // want to build a string first. The positions are in the host's own
+    // coordinates, and errors refer to them.
+    pub fn parse_chars(picture: impl IntoIterator<Item = (usize, char)>) -> Result<Self, Error> {
+        Self::parse_chars_with_options(picture, &FormatOptions::default())
+    }
+
+    // parse, taking the options into account; in strict mode, we report
+    // format tokens we don't support instead of falling back. The picture
+    // keeps the options, so format and the methods like it format with
+    // them, as in the language of the options.
+    pub fn parse_with_options(picture: &str, options: &FormatOptions) -> Result<Self, Error> {
+        Self::parse_chars_with_options(picture.char_indices(), options)
+    }
+
+    fn parse_chars_with_options(
+        picture: impl IntoIterator<Item = (usize, char)>,
+        options: &FormatOptions,
+    ) -> Result<Self, Error> {
+        let (positions, chars): (Vec<usize>, Vec<char>) = picture.into_iter().unzip();
+        // errors at the end are reported just past the last character
+        let end = positions
+            .last()
+            .zip(chars.last())
+            .map_or(0, |(position, c)| position + c.len_utf8());
+        Self::parse_picture(&chars, options).map_err(|error| {
+            error.map_picture_position(|index| positions.get(index).copied().unwrap_or(end))
+        })
+    }
 
-        let regular = Self::create_regular(&signs, digit_family);
-        Ok(if let Some(regular) = regular {
-            Self::Regular(regular)
+    // parse the characters of a picture, where errors are reported by the
+    // index of the character
+    fn parse_picture(chars: &[char], options: &FormatOptions) -> Result<Self, Error> {
+        // the format modifier follows the last semicolon, if there is one
+        let (token, modifier) = match chars.iter().rposition(|c| *c == ';') {
+            Some(index) => (
+                &chars[..index],
+                Modifier::parse(&chars[index + 1..])
+                    .map_err(|error| error.map_picture_position(|i| index + 1 + i))?,
+            ),
+            None => (chars, Modifier::DEFAULT),
+        };
+        // the index the primary token starts at, once leading placeholders
+        // are split off
+        let mut start = 0;
+        let (token, sign, currency, scale) = if options.extensions() {
+            let (rest, sign) = SignPlaceholder::parse(token);
+            if !sign.trailing {
+                start += token.len() - rest.len();
+            }
+            let (token, currency) = CurrencyPlaceholder::parse(rest, options);
+            if !currency.as_ref().is_some_and(|currency| currency.trailing) {
+                start += rest.len() - token.len();
+            }
+            let (token, scale) = parse_scale(token);
+            (token, sign, currency, scale)
         } else {
-            Self::NonRegular(NonRegular::new(signs, digit_family))
+            (token, SignPlaceholder::default(), None, 0)
+        };
+        let token = PrimaryToken::parse(token, modifier.traditional, options).map_err(|error| {
+            let end = start + token.len();
+            error
+                .map_picture_position(|i| start + i)
+                .suggest_picture(|| {
+                    let suggestion = suggest_pattern(token)?;
+                    Some(
+                        chars[..start]
+                            .iter()
+                            .chain(&suggestion)
+                            .chain(&chars[end..])
+                            .collect(),
+                    )
+                })
+        })?;
+        Ok(Self {
+            source: chars.iter().collect(),
+            options: options.clone(),
+            token,
+            numbering: modifier.numbering,
+            variation: modifier.variation,
+            sign,
+            scale,
+            currency,
         })
     }
 
-    fn parse(pattern: &str) -> Result<(Vec<Sign>, Option<DigitFamily>), Error> {
-        let mut mandatory_seen = false;
-        let mut digit_family = None;
+    // a hash of the compiled picture that is stable across runs, so it can
+    // be used to key persistent caches. Pictures that compile to the same
+    // thing (like "#,##0" and "#,##1") have the same fingerprint; the
+    // options they were parsed with count too, as they format with them.
+    pub fn fingerprint(&self) -> u64 {
+        let mut f = Fingerprinter::new();
+        match &self.token {
+            PrimaryToken::DecimalDigitPattern(pattern) => pattern.fingerprint(&mut f),
+            // patterns start with 0 or 1
+            PrimaryToken::Words(casing, _) => {
+                f.write_u8(2);
+                casing.fingerprint(&mut f);
+            }
+            PrimaryToken::Roman(casing) => {
+                f.write_u8(3);
+                casing.fingerprint(&mut f);
+            }
+            PrimaryToken::Alphabetic(alphabet, casing) => {
+                f.write_u8(4);
+                casing.fingerprint(&mut f);
+                alphabet.fingerprint(&mut f);
+            }
+            PrimaryToken::Traditional(system) => {
+                f.write_u8(5);
+                system.fingerprint(&mut f);
+            }
+            PrimaryToken::Cjk(numerals) => {
+                f.write_u8(6);
+                numerals.fingerprint(&mut f);
+            }
+            PrimaryToken::Circled => f.write_u8(7),
+            PrimaryToken::Ethiopic => f.write_u8(8),
+        }
+        if self.numbering == Numbering::Ordinal {
+            f.write_u8(1);
+        }
+        if self.sign != SignPlaceholder::default() {
+            f.write_u8(2);
+            self.sign.fingerprint(&mut f);
+        }
+        if self.scale > 0 {
+            f.write_u8(3);
+            f.write_usize(self.scale);
+        }
+        if let Some(currency) = &self.currency {
+            f.write_u8(4);
+            currency.fingerprint(&mut f);
+        }
+        if let PrimaryToken::Words(_, true) = self.token {
+            f.write_u8(6);
+        }
+        if let Some(variation) = &self.variation {
+            f.write_u8(7);
+            f.write_usize(variation.len());
+            f.write(variation.as_bytes());
+        }
+        // the picture formats with the options it was parsed with
+        f.write_u8(8);
+        self.options.fingerprint(&mut f);
+        f.finish()
+    }
 
-        let signs: Result<Vec<Sign>, Error> = pattern
-            .chars()
-            .map(|c| {
-                if c == '#' {
-                    // optional digit
-                    if !mandatory_seen {
-                        Ok(Sign::OptionalDigit)
-                    } else {
-                        Err(Error::InvalidPictureString)
-                    }
-                } else if is_group_separator(c) {
-                    // group separator
-                    Ok(Sign::GroupSeparator(c))
-                } else {
-                    // mandatory digit
-                    let found_digit_family =
-                        DigitFamily::new(c).ok_or(Error::InvalidPictureString)?;
-                    if let Some(digit_family) = digit_family {
-                        if found_digit_family != digit_family {
-                            return Err(Error::InvalidPictureString);
-                        }
-                    } else {
-                        digit_family = Some(found_digit_family);
-                    }
-                    mandatory_seen = true;
-                    Ok(Sign::MandatoryDigit)
+    // a description of the picture, for validate_integer_picture
+    pub(crate) fn info(&self) -> PictureInfo {
+        let (kind, pattern) = match &self.token {
+            PrimaryToken::DecimalDigitPattern(pattern) => (TokenKind::DecimalDigits, Some(pattern)),
+            PrimaryToken::Words(..) => (TokenKind::Words, None),
+            PrimaryToken::Roman(_) => (TokenKind::Roman, None),
+            PrimaryToken::Alphabetic(..) => (TokenKind::Alphabetic, None),
+            PrimaryToken::Traditional(_) => (TokenKind::Traditional, None),
+            PrimaryToken::Circled => (TokenKind::Circled, None),
+            PrimaryToken::Ethiopic => (TokenKind::Ethiopic, None),
+            PrimaryToken::Cjk(_) => (TokenKind::Cjk, None),
+        };
+        PictureInfo {
+            kind,
+            grouping: pattern.and_then(|pattern| match &pattern.grouping {
+                Grouping::Regular { separator, size } => Some(GroupingInfo::Regular {
+                    separator: *separator,
+                    size: *size,
+                }),
+                Grouping::Irregular(separators) if separators.is_empty() => None,
+                Grouping::Irregular(separators) => {
+                    Some(GroupingInfo::Irregular(separators.clone()))
                 }
-            })
-            .collect();
-        Ok((signs?, digit_family))
+            }),
+            zero_digit: pattern.map(|pattern| pattern.digit_family.map_or('0', |f| f.zero())),
+            ordinal: self.numbering == Numbering::Ordinal,
+        }
     }
 
-    fn validate(pattern: &[Sign]) -> Result<(), Error> {
-        let mut signs = pattern.iter().peekable();
+    // whether ordering the output for non-negative values by length and then
+    // lexicographically is the same as ordering the values numerically.
+    // This holds for every decimal digit pattern: the digits of a family
+    // are consecutive code points, and values with the same amount of
+    // digits get their separators in the same places. It doesn't hold for
+    // words.
+    pub fn sortable(&self) -> bool {
+        // scaling maps different values to the same output
+        if self.scale > 0 {
+            return false;
+        }
+        match &self.token {
+            PrimaryToken::DecimalDigitPattern(_) => true,
+            // shorter sequences of letters are smaller, and sequences of
+            // the same length are in alphabetical order. Zero is written as
+            // "0", which comes before any letter.
+            PrimaryToken::Alphabetic(..) => true,
+            PrimaryToken::Words(..)
+            | PrimaryToken::Roman(_)
+            | PrimaryToken::Traditional(_)
+            | PrimaryToken::Cjk(_)
+            | PrimaryToken::Circled
+            | PrimaryToken::Ethiopic => false,
+        }
+    }
+
+    // format, taking the options into account
+    pub fn format_with_options(&self, i: IBig, options: &FormatOptions) -> Result<String, Error> {
+        self.format_value_with_options(&i, options)
+    }
+
+    // as format_with_options, but for any integer type
+    pub fn format_value_with_options<I: Integer + ?Sized>(
+        &self,
+        i: &I,
+        options: &FormatOptions,
+    ) -> Result<String, Error> {
+        let mut output = String::new();
+        with_digits(i, &mut String::new(), |is_negative, digits| {
+            self.format_digits_with_options(
+                is_negative,
+                digits,
+                options,
+                &mut String::new(),
+                &mut output,
+            )
+        })?;
+        Ok(output)
+    }
 
-        if matches!(signs.peek(), Some(Sign::GroupSeparator(_))) {
-            return Err(Error::InvalidPictureString);
+    // format a value given as its ascii digits, appending the output to
+    // output, or nothing if it's too long. This is where all the limits on
+    // the output are enforced.
+    pub(crate) fn format_digits_with_options(
+        &self,
+        is_negative: bool,
+        digits: &str,
+        options: &FormatOptions,
+        scratch: &mut String,
+        output: &mut String,
+    ) -> Result<(), Error> {
+        let max_output_len = options.max_output_len();
+        // we calculate the length of patterns up front, so that we don't
+        // produce huge output only to throw it away
+        if let Some(max_output_len) = max_output_len {
+            if self
+                .pattern_output_len(is_negative, digits, options)
+                .is_some_and(|len| len > max_output_len)
+            {
+                return Err(Error::OutputTooLong);
+            }
         }
+        let start = output.len();
+        self.format_digits_to_sink(is_negative, digits, options, scratch, output);
+        if options.nfc() {
+            nfc_from(output, start);
+        }
+        // other output is at most a constant factor longer than the digits,
+        // so we can afford to check it once it's produced; normalizing can
+        // make any output longer
+        match max_output_len {
+            Some(max_output_len) if output.len() - start > max_output_len => {
+                output.truncate(start);
+                Err(Error::OutputTooLong)
+            }
+            _ => Ok(()),
+        }
+    }
 
-        while let Some(sign) = signs.next() {
-            match sign {
-                Sign::OptionalDigit => {
-                    if !matches!(
-                        signs.peek(),
-                        Some(Sign::OptionalDigit)
-                            | Some(Sign::GroupSeparator(_))
-                            | Some(Sign::MandatoryDigit)
-                    ) {
-                        return Err(Error::InvalidPictureString);
-                    }
-                }
-                Sign::GroupSeparator(_) => {
-                    if matches!(signs.peek(), Some(Sign::GroupSeparator(_)) | None) {
-                        return Err(Error::InvalidPictureString);
-                    }
+    // the length in bytes of the output for a decimal digit pattern, or
+    // None for other pictures and for scaled values, which we have to
+    // format to know
+    fn pattern_output_len(
+        &self,
+        is_negative: bool,
+        digits: &str,
+        options: &FormatOptions,
+    ) -> Option<usize> {
+        let PrimaryToken::DecimalDigitPattern(pattern) = &self.token else {
+            return None;
+        };
+        if self.scale > 0 {
+            return None;
+        }
+        let mut len = self.max_pattern_len(
+            pattern,
+            digits.len(),
+            options.digit_family().or(pattern.digit_family()),
+        );
+        if self.numbering == Numbering::Ordinal {
+            // the maximum leaves room for the longest suffix
+            let suffix = Spelling::new(options.language(), options.number_spellers(), false)
+                .ordinal_suffix(digits);
+            len = len - words::ORDINAL_SUFFIX_LEN + suffix.len();
+        }
+        // the maximum always leaves room for a sign
+        Some(match (is_negative, options.accounting()) {
+            // parentheses take one more byte than the sign
+            (true, true) => len + 1,
+            (true, false) => len,
+            (false, _) if self.sign.always => len,
+            (false, _) => len - 1,
+        })
+    }
+
+    // the maximum length in bytes of the output for a value with the given
+    // amount of digits, including room for a sign, with the options the
+    // picture was parsed with. This is a const fn so it can be used when
+    // sizing output buffers.
+    pub const fn max_formatted_len(&self, digit_count: usize) -> usize {
+        self.max_formatted_len_with_options(digit_count, &self.options)
+    }
+
+    // as max_formatted_len, for formatting with other options
+    pub const fn max_formatted_len_with_options(
+        &self,
+        digit_count: usize,
+        options: &FormatOptions,
+    ) -> usize {
+        let len = self.max_len_with_sign(digit_count, options);
+        if options.accounting() {
+            // parentheses take one more byte than the sign
+            len.saturating_add(1)
+        } else {
+            len
+        }
+    }
+
+    const fn max_len_with_sign(&self, digit_count: usize, options: &FormatOptions) -> usize {
+        let pattern = match &self.token {
+            PrimaryToken::DecimalDigitPattern(pattern) => pattern,
+            PrimaryToken::Words(..) => {
+                let len = words::max_len(digit_count).saturating_add(words::NEGATIVE_WORD_MAX_LEN);
+                return match self.numbering {
+                    Numbering::Cardinal => len,
+                    Numbering::Ordinal => len.saturating_add(words::ORDINAL_MAX_EXTRA_LEN),
+                };
+            }
+            PrimaryToken::Roman(_) => {
+                // values without a Roman numeral are written in decimal
+                let roman_len = roman::max_len(
+                    digit_count,
+                    options.roman_overflow(),
+                    options.roman_number_forms(),
+                );
+                let len = if digit_count > roman_len {
+                    digit_count
+                } else {
+                    roman_len
+                };
+                return len.saturating_add(1);
+            }
+            PrimaryToken::Traditional(system) => {
+                // values without a numeral are written in decimal
+                let len = if digit_count > system.max_len() {
+                    digit_count
+                } else {
+                    system.max_len()
+                };
+                return len.saturating_add(1);
+            }
+            PrimaryToken::Ethiopic => {
+                // zero is written in decimal, which is shorter
+                return ethiopic::max_len(digit_count).saturating_add(1);
+            }
+            PrimaryToken::Circled => {
+                // values without a circled number are written in decimal
+                let len = if digit_count > circled::MAX_LEN {
+                    digit_count
+                } else {
+                    circled::MAX_LEN
+                };
+                return len.saturating_add(1);
+            }
+            PrimaryToken::Cjk(_) => {
+                // values too large for the numerals are written in decimal
+                let len = if digit_count > cjk::MAX_LEN {
+                    digit_count
+                } else {
+                    cjk::MAX_LEN
+                };
+                return len.saturating_add(1);
+            }
+            PrimaryToken::Alphabetic(alphabet, _) => {
+                // there are never more letters than decimal digits
+                let len = if digit_count > 1 { digit_count } else { 1 };
+                return len
+                    .saturating_mul(alphabet.max_letter_len())
+                    .saturating_add(1);
+            }
+        };
+        // the digit family of the options overrides the one of the pattern
+        let digit_family = match options.digit_family() {
+            Some(digit_family) => Some(digit_family),
+            None => pattern.digit_family(),
+        };
+        self.max_pattern_len(pattern, digit_count, digit_family)
+    }
+
+    const fn max_pattern_len(
+        &self,
+        pattern: &Pattern,
+        digit_count: usize,
+        digit_family: Option<DigitFamily>,
+    ) -> usize {
+        let mandatory_digit_max = pattern.mandatory_digit_max();
+        let total = if digit_count > mandatory_digit_max {
+            digit_count
+        } else {
+            mandatory_digit_max
+        };
+        let digit_len = match digit_family {
+            Some(digit_family) => digit_family.zero().len_utf8(),
+            None => 1,
+        };
+        total
+            .saturating_mul(digit_len)
+            .saturating_add(pattern.separators_len(total))
+            .saturating_add(1)
+            .saturating_add(match self.numbering {
+                Numbering::Cardinal => 0,
+                Numbering::Ordinal => words::ORDINAL_SUFFIX_LEN,
+            })
+            .saturating_add(match &self.currency {
+                Some(currency) => currency.text.len(),
+                None => 0,
+            })
+    }
+
+    // the width in characters of the shortest output, for table layouts
+    // that reserve space for a column. A decimal digit pattern is at least
+    // as wide as its mandatory digits with their separators; anything else
+    // takes at least one character. Ordinal suffixes depend on the
+    // language, so they aren't counted.
+    pub fn min_width(&self) -> usize {
+        match &self.token {
+            PrimaryToken::DecimalDigitPattern(pattern) => {
+                let width = self.pattern_width(pattern, pattern.mandatory_digit_max());
+                if self.sign.always {
+                    width + 1
+                } else {
+                    width
                 }
-                _ => {}
             }
+            _ => 1,
+        }
+    }
+
+    // the width in characters of the widest output for a value with the
+    // given amount of digits, including room for a sign. Since values
+    // have no limit, the width is only bounded for a bounded amount of
+    // digits; for tokens other than decimal digit patterns, this is
+    // max_formatted_len, as no character takes less than a byte.
+    pub fn max_width(&self, digit_count: usize) -> usize {
+        let PrimaryToken::DecimalDigitPattern(pattern) = &self.token else {
+            return self.max_formatted_len(digit_count);
+        };
+        let width = self
+            .pattern_width(pattern, digit_count.max(pattern.mandatory_digit_max()))
+            .saturating_add(1)
+            .saturating_add(self.options.accounting() as usize);
+        match self.numbering {
+            Numbering::Cardinal => width,
+            // no suffix has more characters than bytes
+            Numbering::Ordinal => width.saturating_add(words::ORDINAL_SUFFIX_LEN),
         }
-        Ok(())
     }
 
-    fn create_regular(signs: &[Sign], digit_family: Option<DigitFamily>) -> Option<Regular> {
-        let mut last_separator = None;
-        let mut last_count = None;
-        let mut count = 0;
-        let mut mandatory_digit_max_count = 0;
+    // the width in characters of a pattern with the given amount of digits
+    // and its currency, without sign or suffix
+    fn pattern_width(&self, pattern: &Pattern, total: usize) -> usize {
+        total
+            .saturating_add(pattern.grouping.separators_count(total))
+            .saturating_add(
+                self.currency
+                    .as_ref()
+                    .map_or(0, |currency| currency.text.chars().count()),
+            )
+    }
 
-        for sign in signs.iter().rev() {
-            match sign {
-                Sign::GroupSeparator(c) => {
-                    if let Some(last_separator) = last_separator {
-                        if last_separator != *c {
-                            return None;
-                        }
-                    } else {
-                        last_separator = Some(*c);
-                    }
-                    if let Some(last_count) = last_count {
-                        if count != last_count {
-                            return None;
-                        }
-                    } else {
-                        last_count = Some(count);
-                    }
-                    count = 0;
-                }
-                Sign::MandatoryDigit { .. } => {
-                    mandatory_digit_max_count += 1;
-                    count += 1;
-                }
-                Sign::OptionalDigit => {
-                    count += 1;
+    // format a value with the options the picture was parsed with; a
+    // picture parsed once can format any amount of values. There's no
+    // limit on the length of the output, whatever the options say; use
+    // format_with_options to enforce it.
+    pub fn format(&self, i: &IBig) -> String {
+        self.format_value(i)
+    }
+
+    // as format, but for any integer type; machine integers are formatted
+    // without going through an IBig
+    pub fn format_value<I: Integer + ?Sized>(&self, i: &I) -> String {
+        let mut output = String::new();
+        self.format_value_to_sink(i, &mut output);
+        if self.options.nfc() {
+            nfc_from(&mut output, 0);
+        }
+        output
+    }
+
+    // format, appending the output to out, so that a buffer can be reused
+    // for many values. Errors of out are passed on. As with the sink
+    // methods, the output isn't normalized, even if the options ask for
+    // it, as it's handed over in pieces.
+    pub fn format_into<W: fmt::Write + ?Sized>(&self, i: &IBig, out: &mut W) -> fmt::Result {
+        let mut sink = WriteSink::new(out);
+        self.format_to_sink(i, &mut sink);
+        sink.finish()
+    }
+
+    // format, handing the output in chunks to the sink, from left to right.
+    // Digits between separators are handed over in one chunk.
+    pub fn format_to_sink<S: FormatSink + ?Sized>(&self, i: &IBig, sink: &mut S) {
+        self.format_value_to_sink(i, sink);
+    }
+
+    // as format_to_sink, but with the options' limit on the output length.
+    // Nothing is handed over for output that's too long: the length of a
+    // pattern's output is known up front, and other output is collected
+    // until it's known to fit.
+    pub(crate) fn format_to_sink_with_options<S: FormatSink + ?Sized>(
+        &self,
+        i: &IBig,
+        options: &FormatOptions,
+        sink: &mut S,
+    ) -> Result<(), Error> {
+        with_digits(i, &mut String::new(), |is_negative, digits| {
+            let Some(max_output_len) = options.max_output_len() else {
+                self.format_digits_to_sink(is_negative, digits, options, &mut String::new(), sink);
+                return Ok(());
+            };
+            if let Some(len) = self.pattern_output_len(is_negative, digits, options) {
+                if len > max_output_len {
+                    return Err(Error::OutputTooLong);
                 }
+                self.format_digits_to_sink(is_negative, digits, options, &mut String::new(), sink);
+                return Ok(());
+            }
+            let mut chunks: Vec<(ChunkKind, String)> = Vec::new();
+            self.format_digits_to_sink(
+                is_negative,
+                digits,
+                options,
+                &mut String::new(),
+                &mut chunks,
+            );
+            if chunks.iter().map(|(_, chunk)| chunk.len()).sum::<usize>() > max_output_len {
+                return Err(Error::OutputTooLong);
+            }
+            for (kind, chunk) in &chunks {
+                sink.write_chunk(*kind, chunk);
+            }
+            Ok(())
+        })
+    }
+
+    // the options the picture was parsed with
+    pub(crate) fn options(&self) -> &FormatOptions {
+        &self.options
+    }
+
+    // as format_to_sink, but for any integer type, so that values don't
+    // have to be converted to an IBig first
+    pub fn format_value_to_sink<I: Integer + ?Sized, S: FormatSink + ?Sized>(
+        &self,
+        i: &I,
+        sink: &mut S,
+    ) {
+        // machine integers are written on the stack; an empty scratch
+        // doesn't allocate until it's needed
+        with_digits(i, &mut String::new(), |is_negative, s| {
+            self.format_digits_to_sink(is_negative, s, &self.options, &mut String::new(), sink)
+        });
+    }
+
+    // format a value given as its ascii digits, using scratch as a buffer
+    // to transliterate digits in
+    pub(crate) fn format_digits_to_sink<S: FormatSink + ?Sized>(
+        &self,
+        is_negative: bool,
+        s: &str,
+        options: &FormatOptions,
+        scratch: &mut String,
+        sink: &mut S,
+    ) {
+        let scaled;
+        let (is_negative, s) = if self.scale > 0 {
+            scaled = scale_digits(s, self.scale);
+            // a negative value can round to zero, which has no sign
+            (is_negative && scaled != "0", scaled.as_str())
+        } else {
+            (is_negative, s)
+        };
+        if is_negative && options.accounting() {
+            // the parentheses replace the sign, including a plus sign of a
+            // sign placeholder
+            sink.write_chunk(ChunkKind::Sign, "(");
+            self.format_scaled_to_sink(false, true, s, options, scratch, sink);
+            sink.write_chunk(ChunkKind::Sign, ")");
+        } else {
+            self.format_scaled_to_sink(is_negative, false, s, options, scratch, sink);
+        }
+    }
+
+    // in_parentheses is whether the value is negative and put in
+    // parentheses, so it gets no sign
+    fn format_scaled_to_sink<S: FormatSink + ?Sized>(
+        &self,
+        is_negative: bool,
+        in_parentheses: bool,
+        s: &str,
+        options: &FormatOptions,
+        scratch: &mut String,
+        sink: &mut S,
+    ) {
+        let pattern = match &self.token {
+            PrimaryToken::DecimalDigitPattern(pattern) => pattern,
+            PrimaryToken::Words(casing, traditional) => {
+                let language =
+                    Spelling::new(options.language(), options.number_spellers(), *traditional);
+                if is_negative {
+                    scratch.clear();
+                    scratch.push_str(language.negative_word());
+                    casing.apply(scratch, &language.identifier());
+                    sink.write_chunk(ChunkKind::Sign, scratch);
+                }
+                scratch.clear();
+                match self.numbering {
+                    Numbering::Cardinal => language.write_cardinal(s, scratch),
+                    Numbering::Ordinal => {
+                        language.write_ordinal(s, self.variation.as_deref(), scratch)
+                    }
+                }
+                casing.apply(scratch, &language.identifier());
+                sink.write_chunk(ChunkKind::Words, scratch);
+                return;
+            }
+            PrimaryToken::Roman(_)
+            | PrimaryToken::Alphabetic(..)
+            | PrimaryToken::Traditional(_)
+            | PrimaryToken::Ethiopic
+            | PrimaryToken::Circled
+            | PrimaryToken::Cjk(_) => {
+                // a negative value is a minus sign followed by its absolute
+                // value. Values the numerals have no representation for,
+                // like zero in Roman numerals, are written in decimal, as
+                // the specification prescribes.
+                if is_negative {
+                    sink.write_chunk(ChunkKind::Sign, "-");
+                }
+                scratch.clear();
+                if self.token.write_numeral(s, options, scratch) {
+                    sink.write_chunk(ChunkKind::Digits, scratch);
+                } else {
+                    sink.write_chunk(ChunkKind::Digits, s);
+                }
+                return;
+            }
+        };
+        // the amount of zeros we want to produce is the amount of
+        // mandatory digits minus the digits we already produce
+        let zeros_amount = pattern.mandatory_digit_max().saturating_sub(s.len());
+        let total = zeros_amount + s.len();
+        // the digit family of the options overrides the one of the pattern;
+        // ascii digits don't need transliteration
+        let digit_family = options
+            .digit_family()
+            .or(pattern.digit_family())
+            .filter(|f| f.zero() != '0');
+
+        let sign = match (is_negative, self.sign.always && !in_parentheses) {
+            (true, _) => Some("-"),
+            (false, true) => Some("+"),
+            (false, false) => None,
+        };
+        if let (Some(sign), false) = (sign, self.sign.trailing) {
+            sink.write_chunk(ChunkKind::Sign, sign);
+        }
+        if let Some(currency) = self.currency.as_ref().filter(|c| !c.trailing) {
+            sink.write_chunk(ChunkKind::Currency, &currency.text);
+        }
+
+        // we go through the digit positions from the left, and whenever the
+        // pattern wants a separator there, we emit the group of digits before
+        // it and then the separator. There's never a separator at the very
+        // end.
+        let mut group_start = 0;
+        let mut separator_buf = [0; 4];
+        for digits_to_the_right in (1..total).rev() {
+            if let Some(separator) = pattern.separator_at(digits_to_the_right) {
+                let group_end = total - digits_to_the_right;
+                Self::write_digits(
+                    digit_family,
+                    s,
+                    zeros_amount,
+                    group_start..group_end,
+                    scratch,
+                    sink,
+                );
+                sink.write_chunk(
+                    ChunkKind::Separator,
+                    separator.encode_utf8(&mut separator_buf),
+                );
+                group_start = group_end;
+            }
+        }
+        Self::write_digits(
+            digit_family,
+            s,
+            zeros_amount,
+            group_start..total,
+            scratch,
+            sink,
+        );
+        if self.numbering == Numbering::Ordinal {
+            let language = Spelling::new(options.language(), options.number_spellers(), false);
+            sink.write_chunk(ChunkKind::Suffix, language.ordinal_suffix(s));
+        }
+        if let Some(currency) = self.currency.as_ref().filter(|c| c.trailing) {
+            sink.write_chunk(ChunkKind::Currency, &currency.text);
+        }
+        if let (Some(sign), true) = (sign, self.sign.trailing) {
+            sink.write_chunk(ChunkKind::Sign, sign);
+        }
+    }
+
+    // write the digits in the given range of positions, where the positions
+    // include the zero padding
+    fn write_digits<S: FormatSink + ?Sized>(
+        digit_family: Option<DigitFamily>,
+        s: &str,
+        zeros_amount: usize,
+        positions: std::ops::Range<usize>,
+        scratch: &mut String,
+        sink: &mut S,
+    ) {
+        // if we don't need to transliterate or pad, we can hand over a slice
+        // of the digits directly
+        if digit_family.is_none() && positions.start >= zeros_amount {
+            sink.write_chunk(
+                ChunkKind::Digits,
+                &s[positions.start - zeros_amount..positions.end - zeros_amount],
+            );
+            return;
+        }
+        scratch.clear();
+        for position in positions {
+            let digit = if position < zeros_amount {
+                '0'
+            } else {
+                // digits are ascii, so the byte offsets are char offsets
+                s.as_bytes()[position - zeros_amount] as char
+            };
+            scratch.push(match (digit_family, AsciiDigit::new(digit)) {
+                (Some(digit_family), Some(digit)) => digit_family.digit(digit),
+                _ => digit,
+            });
+        }
+        sink.write_chunk(ChunkKind::Digits, scratch);
+    }
+}
+
+// a picture displays as the picture string it was parsed from, so that
+// parsing what it displays as gives the same picture
+impl fmt::Display for Picture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+pub fn format_integer(i: IBig, picture: &str) -> Result<String, Error> {
+    let picture = Picture::parse(picture)?;
+    Ok(picture.format(&i))
+}
+
+// as format_integer, but for any integer type, like the i64 most values
+// fit in, which is formatted without allocating an IBig
+pub fn format_integer_value<I: Integer + ?Sized>(i: &I, picture: &str) -> Result<String, Error> {
+    let picture = Picture::parse(picture)?;
+    Ok(picture.format_value(i))
+}
+
+// format in the given language, which affects words and ordinals. Numbers
+// are spelled out in English, German and Korean; for any other language we
+// fall back to English.
+pub fn format_integer_lang(i: IBig, picture: &str, lang: &str) -> Result<String, Error> {
+    let options = FormatOptions::builder().language(lang).build()?;
+    format_integer_with_options(i, picture, &options)
+}
+
+pub fn format_integer_with_options(
+    i: IBig,
+    picture: &str,
+    options: &FormatOptions,
+) -> Result<String, Error> {
+    Picture::parse_with_options(picture, options)?.format_with_options(i, options)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::roman::RomanOverflow;
+
+    fn pattern_output_len(picture: &Picture, i: &IBig, options: &FormatOptions) -> Option<usize> {
+        with_digits(i, &mut String::new(), |is_negative, digits| {
+            picture.pattern_output_len(is_negative, digits, options)
+        })
+    }
+
+    fn invalid(position: usize, character: char) -> Error {
+        Error::InvalidPictureString(PictureError::new(position, Some(character)))
+    }
+
+    fn invalid_end(position: usize) -> Error {
+        Error::InvalidPictureString(PictureError::new(position, None))
+    }
+
+    fn invalid_modifier(position: usize, character: char) -> Error {
+        Error::InvalidFormatModifier(PictureError::new(position, Some(character)))
+    }
+
+    fn suggesting(error: Error, suggestion: &str) -> Error {
+        error.suggest_picture(|| Some(suggestion.to_string()))
+    }
+
+    #[test]
+    fn test_format_integer() {
+        assert_eq!(format_integer(123.into(), "1").unwrap(), "123");
+    }
+
+    #[test]
+    fn test_format_zero_padded_integer() {
+        assert_eq!(format_integer(123.into(), "0000").unwrap(), "0123");
+    }
+
+    #[test]
+    fn test_format_zero_padded_integer_negative() {
+        assert_eq!(format_integer((-123).into(), "00000").unwrap(), "-00123");
+    }
+
+    #[test]
+    fn test_format_zero_padded_integer_negative_shorter() {
+        assert_eq!(format_integer((-123).into(), "0000").unwrap(), "-0123");
+    }
+
+    #[test]
+    fn test_format_with_thousands_separator() {
+        assert_eq!(format_integer(1234.into(), "0,000").unwrap(), "1,234");
+    }
+
+    #[test]
+    fn test_format_with_thousands_separator2() {
+        assert_eq!(format_integer(4321.into(), "0,000").unwrap(), "4,321");
+    }
+
+    #[test]
+    fn test_format_with_thousands_separator_and_zero_prefix() {
+        assert_eq!(format_integer(4321.into(), "00,000").unwrap(), "04,321");
+    }
+
+    #[test]
+    fn test_illegal_primary_token() {
+        assert_eq!(Picture::parse("0b0"), Err(invalid(1, 'b')));
+    }
+
+    #[test]
+    fn test_illegal_decimal_digit_pattern_with_adjacent_grouping_separators() {
+        assert_eq!(
+            Picture::parse("0,,0"),
+            Err(suggesting(invalid(2, ','), "0,0"))
+        );
+    }
+
+    #[test]
+    fn test_illegal_decimal_digit_pattern_with_starting_grouping_separator() {
+        assert_eq!(Picture::parse(",0"), Err(suggesting(invalid(0, ','), "0")));
+    }
+
+    #[test]
+    fn test_illegal_decimal_digit_pattern_with_ending_grouping_separator() {
+        assert_eq!(Picture::parse("0,"), Err(suggesting(invalid(1, ','), "0")));
+    }
+
+    #[test]
+    fn test_optional_digit_sign_before_mandatory_digit_sign() {
+        assert!(Picture::parse("#0").is_ok());
+    }
+
+    #[test]
+    fn test_optional_digit_by_itself_is_illegal() {
+        assert_eq!(Picture::parse("#"), Err(suggesting(invalid(0, '#'), "0")));
+    }
+
+    #[test]
+    fn test_optional_digit_sign_after_mandatory_digit_sign_is_illegal() {
+        assert_eq!(
+            Picture::parse("0#0"),
+            Err(suggesting(invalid(1, '#'), "#00"))
+        );
+    }
+
+    #[test]
+    fn test_optional_digit_sign_after_grouping_separator_and_mandatory_digit_sign_is_illegal() {
+        assert_eq!(
+            Picture::parse("0,#0"),
+            Err(suggesting(invalid(2, '#'), "#,00"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_picture_position() {
+        // positions are byte offsets
+        assert_eq!(
+            Picture::parse("١٢,"),
+            Err(suggesting(invalid(4, ','), "١٢"))
+        );
+        assert_eq!(Picture::parse("١;x"), Err(invalid_modifier(3, 'x')));
+        // past the end when nothing's there
+        assert_eq!(Picture::parse_chars([]), Err(invalid_end(0)));
+        let error = Picture::parse("0,").unwrap_err();
+        match error {
+            Error::InvalidPictureString(error) => {
+                assert_eq!(error.position(), 1);
+                assert_eq!(error.character(), Some(','));
+                assert_eq!(error.code(), "FODF1310");
+            }
+            _ => panic!("{:?}", error),
+        }
+    }
+
+    #[test]
+    fn test_invalid_picture_position_in_host_coordinates() {
+        let chars = [(10, '0'), (20, ','), (30, ','), (40, '0')];
+        assert_eq!(
+            Picture::parse_chars(chars),
+            Err(suggesting(invalid(30, ','), "0,0"))
+        );
+        let chars = [(10, ';'), (20, 'o')];
+        assert_eq!(Picture::parse_chars(chars), Err(invalid_end(10)));
+        // the end is just past the last character
+        let chars = [(10, '1'), (20, ';'), (30, 'o'), (40, '(')];
+        assert_eq!(Picture::parse_chars(chars), Err(invalid_modifier(40, '(')));
+    }
+
+    #[test]
+    fn test_empty_picture_is_illegal() {
+        // the primary format token must not be zero-length
+        assert_eq!(Picture::parse(""), Err(invalid_end(0)));
+    }
+
+    #[test]
+    fn test_digit_family_override() {
+        let options = FormatOptions::builder().digit_family('٠').build().unwrap();
+        assert_eq!(
+            format_integer_with_options(1234.into(), "#,##0", &options).unwrap(),
+            "١,٢٣٤"
+        );
+        // the override also goes the other way
+        let options = FormatOptions::builder().digit_family('0').build().unwrap();
+        assert_eq!(
+            format_integer_with_options(12.into(), "٠٠٠", &options).unwrap(),
+            "012"
+        );
+    }
+
+    #[test]
+    fn test_digit_family_override_output_len() {
+        let options = FormatOptions::builder()
+            .digit_family('٠')
+            .max_output_len(4)
+            .build()
+            .unwrap();
+        assert_eq!(
+            format_integer_with_options(12.into(), "0", &options).unwrap(),
+            "١٢"
+        );
+        assert_eq!(
+            format_integer_with_options(123.into(), "0", &options),
+            Err(Error::OutputTooLong)
+        );
+    }
+
+    #[test]
+    fn test_format_to_sink_other_integers() {
+        let picture = Picture::parse("#,##0").unwrap();
+        let mut output = String::new();
+        picture.format_value_to_sink(&u128::MAX, &mut output);
+        assert_eq!(
+            output,
+            "340,282,366,920,938,463,463,374,607,431,768,211,455"
+        );
+        output.clear();
+        picture.format_value_to_sink(&i128::MIN, &mut output);
+        assert_eq!(
+            output,
+            "-170,141,183,460,469,231,731,687,303,715,884,105,728"
+        );
+        output.clear();
+        picture.format_value_to_sink(&ibig::UBig::from(1234u32), &mut output);
+        assert_eq!(output, "1,234");
+    }
+
+    #[test]
+    fn test_format_words() {
+        assert_eq!(
+            format_integer(123.into(), "w").unwrap(),
+            "one hundred and twenty-three"
+        );
+        assert_eq!(format_integer(0.into(), "w").unwrap(), "zero");
+        assert_eq!(
+            format_integer((-1001).into(), "w").unwrap(),
+            "minus one thousand and one"
+        );
+    }
+
+    #[test]
+    fn test_format_words_upper_case() {
+        assert_eq!(
+            format_integer(123.into(), "W").unwrap(),
+            "ONE HUNDRED AND TWENTY-THREE"
+        );
+        assert_eq!(format_integer((-5).into(), "W").unwrap(), "MINUS FIVE");
+    }
+
+    #[test]
+    fn test_format_words_title_case() {
+        assert_eq!(
+            format_integer(123.into(), "Ww").unwrap(),
+            "One Hundred and Twenty-three"
+        );
+        assert_eq!(format_integer((-5).into(), "Ww").unwrap(), "Minus Five");
+    }
+
+    #[test]
+    fn test_words_casing_fingerprints_differ() {
+        let lower = Picture::parse("w").unwrap().fingerprint();
+        assert_ne!(lower, Picture::parse("W").unwrap().fingerprint());
+        assert_ne!(lower, Picture::parse("Ww").unwrap().fingerprint());
+    }
+
+    #[test]
+    fn test_format_words_to_sink() {
+        let mut chunks: Vec<(ChunkKind, String)> = Vec::new();
+        Picture::parse("w")
+            .unwrap()
+            .format_to_sink(&(-12).into(), &mut chunks);
+        assert_eq!(
+            chunks,
+            vec![
+                (ChunkKind::Sign, "minus ".to_string()),
+                (ChunkKind::Words, "twelve".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_words_output_len() {
+        let picture = Picture::parse("w").unwrap();
+        let options = FormatOptions::builder().max_output_len(6).build().unwrap();
+        assert_eq!(
+            picture.format_with_options(12.into(), &options).unwrap(),
+            "twelve"
+        );
+        assert_eq!(
+            picture.format_with_options(13.into(), &options),
+            Err(Error::OutputTooLong)
+        );
+        assert!(!picture.sortable());
+    }
+
+    #[test]
+    fn test_format_ordinal() {
+        assert_eq!(format_integer(1.into(), "1;o").unwrap(), "1st");
+        assert_eq!(format_integer(22.into(), "1;o").unwrap(), "22nd");
+        assert_eq!(format_integer(113.into(), "1;o").unwrap(), "113th");
+        assert_eq!(format_integer((-3).into(), "#,##0;o").unwrap(), "-3rd");
+        assert_eq!(format_integer(1234.into(), "#,##0;o").unwrap(), "1,234th");
+    }
+
+    #[test]
+    fn test_format_ordinal_words() {
+        assert_eq!(format_integer(1.into(), "w;o").unwrap(), "first");
+        assert_eq!(format_integer(23.into(), "w;o").unwrap(), "twenty-third");
+        assert_eq!(
+            format_integer(121.into(), "Ww;o").unwrap(),
+            "One Hundred and Twenty-first"
+        );
+        assert_eq!(format_integer(20.into(), "W;o").unwrap(), "TWENTIETH");
+    }
+
+    #[test]
+    fn test_format_modifier() {
+        assert_eq!(format_integer(2.into(), "1;c").unwrap(), "2");
+        assert_eq!(format_integer(2.into(), "1;").unwrap(), "2");
+        assert_eq!(format_integer(2.into(), "w;ot").unwrap(), "second");
+        assert_eq!(Picture::parse("1;x"), Err(invalid_modifier(2, 'x')));
+        assert_eq!(Picture::parse("1;oo"), Err(invalid_modifier(3, 'o')));
+        // the modifier follows the last semicolon, so earlier ones can be
+        // grouping separators
+        assert_eq!(format_integer(1234.into(), "0;000;o").unwrap(), "1;234th");
+        // the primary token can't be empty
+        assert_eq!(Picture::parse(";o"), Err(invalid_end(0)));
+    }
+
+    #[test]
+    fn test_modifier_errors_are_distinguished() {
+        let error = Picture::parse("#,##0;o(-er").unwrap_err();
+        assert_eq!(error, invalid_modifier(7, '('));
+        assert_eq!(error.message(), "invalid format modifier at 7");
+        // errors in the primary token are still reported as such when the
+        // modifier is fine
+        assert_eq!(
+            Picture::parse("0,;o(-er)").unwrap_err().message(),
+            "invalid picture string at 1"
+        );
+    }
+
+    #[test]
+    fn test_format_modifier_variation() {
+        assert_eq!(
+            format_integer_lang(3.into(), "w;o(-er)", "de").unwrap(),
+            "dritter"
+        );
+        assert_eq!(
+            format_integer_lang(3.into(), "Ww;o(-es)a", "de").unwrap(),
+            "Drittes"
+        );
+        // languages without variations ignore them
+        assert_eq!(format_integer(3.into(), "w;o(-er)").unwrap(), "third");
+        assert_eq!(format_integer(3.into(), "1;o(-º)").unwrap(), "3rd");
+        assert_eq!(format_integer(3.into(), "1;c(x)").unwrap(), "3");
+        assert_ne!(
+            Picture::parse("w;o(-er)").unwrap().fingerprint(),
+            Picture::parse("w;o").unwrap().fingerprint()
+        );
+        assert_eq!(Picture::parse("1;o(-er"), Err(invalid_modifier(3, '(')));
+        assert_eq!(Picture::parse("1;o()"), Err(invalid_modifier(4, ')')));
+        assert_eq!(Picture::parse("1;(-er)"), Err(invalid_modifier(2, '(')));
+    }
+
+    #[test]
+    fn test_format_modifier_grammar() {
+        // the variation runs to the last closing parenthesis, as the
+        // regular expression of the specification is greedy
+        let picture = Picture::parse("1;o(a)b)t").unwrap();
+        assert_eq!(picture.variation.as_deref(), Some("a)b"));
+        assert_eq!(
+            Picture::parse("w;o())a").unwrap().variation.as_deref(),
+            Some(")")
+        );
+        // anything after the variation but a or t is an error where it
+        // starts, rather than part of the picture
+        assert_eq!(Picture::parse("1;o(a)b"), Err(invalid_modifier(6, 'b')));
+        assert_eq!(Picture::parse("1;o(a)tt"), Err(invalid_modifier(7, 't')));
+        assert_eq!(Picture::parse("1;ta"), Err(invalid_modifier(3, 'a')));
+        assert_eq!(Picture::parse("1;co"), Err(invalid_modifier(3, 'o')));
+        assert_eq!(Picture::parse("1;c)"), Err(invalid_modifier(3, ')')));
+        // positions are in the picture, past multi-byte characters
+        assert_eq!(Picture::parse("١;oq"), Err(invalid_modifier(4, 'q')));
+    }
+
+    #[test]
+    fn test_format_with_parse_options() {
+        let german = FormatOptions::builder().language("de").build().unwrap();
+        let picture = Picture::parse_with_options("w", &german).unwrap();
+        assert_eq!(picture.format(&3.into()), "drei");
+        assert_eq!(picture.format_value(&3u8), "drei");
+        let mut output = String::new();
+        picture.format_into(&3.into(), &mut output).unwrap();
+        assert_eq!(output, "drei");
+        // other options override them
+        assert_eq!(
+            picture
+                .format_with_options(3.into(), &FormatOptions::default())
+                .unwrap(),
+            "three"
+        );
+        let options = FormatOptions::builder()
+            .roman_overflow(RomanOverflow::RepeatM(9999))
+            .build()
+            .unwrap();
+        let picture = Picture::parse_with_options("I", &options).unwrap();
+        assert_eq!(picture.format(&4420.into()), "MMMMCDXX");
+        let options = FormatOptions::builder().digit_family('٠').build().unwrap();
+        let picture = Picture::parse_with_options("0", &options).unwrap();
+        assert_eq!(picture.format(&42.into()), "٤٢");
+        let mut chunks: Vec<(ChunkKind, String)> = Vec::new();
+        picture.format_to_sink(&42.into(), &mut chunks);
+        assert_eq!(chunks, vec![(ChunkKind::Digits, "٤٢".to_string())]);
+    }
+
+    #[test]
+    fn test_digit_family_option_max_formatted_len() {
+        let options = FormatOptions::builder().digit_family('٠').build().unwrap();
+        let picture = Picture::parse_with_options("#,##0", &options).unwrap();
+        let output = picture.format(&(-1234).into());
+        assert_eq!(output.len(), picture.max_formatted_len(4));
+    }
+
+    #[test]
+    fn test_display() {
+        for picture in ["#,##0", "#,##1;o(-er)", "Ww", "١٬٢٣٤", "0;000;o"] {
+            let parsed = Picture::parse(picture).unwrap();
+            assert_eq!(parsed.to_string(), picture);
+            assert_eq!(Picture::parse(&parsed.to_string()).unwrap(), parsed);
+        }
+        // pictures from characters display as the characters
+        let picture = Picture::parse_chars([(10, '1'), (12, ';'), (20, 'o')]).unwrap();
+        assert_eq!(picture.to_string(), "1;o");
+        // placeholders display as given, not as what they stand for
+        let options = FormatOptions::builder()
+            .extensions(true)
+            .currency_symbol("€")
+            .build()
+            .unwrap();
+        let picture = Picture::parse_with_options("+¤#,##0", &options).unwrap();
+        assert_eq!(picture.to_string(), "+¤#,##0");
+    }
+
+    #[test]
+    fn test_min_width() {
+        assert_eq!(Picture::parse("#,##0").unwrap().min_width(), 1);
+        assert_eq!(Picture::parse("0,000,000").unwrap().min_width(), 9);
+        // characters, not bytes
+        assert_eq!(Picture::parse("٠٬٠٠٠").unwrap().min_width(), 5);
+        assert_eq!(Picture::parse("w").unwrap().min_width(), 1);
+        let options = FormatOptions::builder()
+            .extensions(true)
+            .currency_symbol("€")
+            .build()
+            .unwrap();
+        let picture = Picture::parse_with_options("+¤ 000", &options).unwrap();
+        assert_eq!(picture.min_width(), 6);
+        assert_eq!(
+            picture.min_width(),
+            picture
+                .format_with_options(1.into(), &options)
+                .unwrap()
+                .chars()
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_max_width() {
+        let picture = Picture::parse("#,##0").unwrap();
+        assert_eq!(picture.max_width(7), 10);
+        assert_eq!(
+            picture.format(&(-1_234_567).into()).chars().count(),
+            picture.max_width(7)
+        );
+        // mandatory digits pad shorter values
+        assert_eq!(Picture::parse("0000").unwrap().max_width(1), 5);
+        let picture = Picture::parse("٠٬٠٠٠").unwrap();
+        assert!(picture.max_width(4) < picture.max_formatted_len(4));
+        let picture = Picture::parse("i").unwrap();
+        assert_eq!(picture.max_width(3), picture.max_formatted_len(3));
+        // the width grows with the digits
+        assert_eq!(
+            Picture::parse("0").unwrap().max_width(usize::MAX),
+            usize::MAX
+        );
+    }
+
+    #[test]
+    fn test_ordinal_max_formatted_len() {
+        // two digits, a sign and the Korean suffix
+        let picture = Picture::parse("1;o").unwrap();
+        assert_eq!(picture.max_formatted_len(2), 9);
+        let picture = Picture::parse("w;o").unwrap();
+        assert!(picture.format(&(-12).into()).len() <= picture.max_formatted_len(2));
+    }
+
+    #[test]
+    fn test_ordinal_fingerprint() {
+        assert_ne!(
+            Picture::parse("1").unwrap().fingerprint(),
+            Picture::parse("1;o").unwrap().fingerprint()
+        );
+        assert_eq!(
+            Picture::parse("1").unwrap().fingerprint(),
+            Picture::parse("1;c").unwrap().fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_format_integer_lang() {
+        assert_eq!(
+            format_integer_lang(123.into(), "w", "de").unwrap(),
+            "einhundertdreiundzwanzig"
+        );
+        assert_eq!(
+            format_integer_lang(3.into(), "w;o", "de-CH").unwrap(),
+            "dritte"
+        );
+        assert_eq!(format_integer_lang(3.into(), "1;o", "de").unwrap(), "3.");
+        assert_eq!(
+            format_integer_lang((-21).into(), "Ww", "de").unwrap(),
+            "Minus Einundzwanzig"
+        );
+        // unsupported languages fall back to English rather than failing
+        assert_eq!(
+            format_integer_lang(3.into(), "w;o", "pt-BR").unwrap(),
+            "third"
+        );
+        assert_eq!(
+            format_integer_lang(101.into(), "w", "en-US").unwrap(),
+            "one hundred one"
+        );
+        assert_eq!(
+            format_integer_lang(101.into(), "w", "en-GB").unwrap(),
+            "one hundred and one"
+        );
+    }
+
+    // Welsh, as far as the first few numbers go
+    struct Welsh;
+
+    impl crate::words::NumberSpeller for Welsh {
+        fn write_cardinal(&self, digits: &str, output: &mut String) {
+            output.push_str(match digits {
+                "1" => "un",
+                "2" => "dau",
+                "3" => "tri",
+                _ => digits,
+            });
+        }
+
+        fn write_ordinal(&self, digits: &str, _: Option<&str>, output: &mut String) {
+            output.push_str(match digits {
+                "1" => "cyntaf",
+                "2" => "ail",
+                _ => digits,
+            });
+        }
+
+        fn negative_word(&self) -> &str {
+            "minws "
+        }
+
+        fn ordinal_suffix(&self, digits: &str) -> &str {
+            match digits {
+                "1" => "af",
+                _ => "ydd",
+            }
+        }
+    }
+
+    #[test]
+    fn test_number_speller() {
+        let options = FormatOptions::builder()
+            .language("cy-GB")
+            .number_speller("cy", Welsh)
+            .build()
+            .unwrap();
+        let format = |i: i32, picture| format_integer_with_options(i.into(), picture, &options);
+        assert_eq!(format(3, "w").unwrap(), "tri");
+        assert_eq!(format(-2, "Ww").unwrap(), "Minws Dau");
+        assert_eq!(format(1, "w;o").unwrap(), "cyntaf");
+        assert_eq!(format(1, "1;o").unwrap(), "1af");
+        assert_eq!(format(12, "1;o").unwrap(), "12ydd");
+    }
+
+    #[test]
+    fn test_number_speller_output_len() {
+        // the suffix is checked against the limit like ours
+        let options = FormatOptions::builder()
+            .language("cy")
+            .number_speller("cy", Welsh)
+            .max_output_len(4)
+            .build()
+            .unwrap();
+        assert_eq!(
+            format_integer_with_options(12.into(), "1;o", &options),
+            Err(Error::OutputTooLong)
+        );
+        assert_eq!(
+            format_integer_with_options(1.into(), "1;o", &options).unwrap(),
+            "1af"
+        );
+    }
+
+    #[test]
+    fn test_nfc() {
+        // a currency symbol with a combining accent
+        let options = FormatOptions::builder()
+            .extensions(true)
+            .currency_symbol("E\u{301}");
+        assert_eq!(
+            format_integer_with_options(5.into(), "¤0", &options.build().unwrap()).unwrap(),
+            "E\u{301}5"
+        );
+        let options = FormatOptions::builder()
+            .extensions(true)
+            .currency_symbol("E\u{301}")
+            .nfc(true)
+            .build()
+            .unwrap();
+        assert_eq!(
+            format_integer_with_options(5.into(), "¤0", &options).unwrap(),
+            "\u{c9}5"
+        );
+    }
+
+    #[test]
+    fn test_format_integer_korean() {
+        assert_eq!(
+            format_integer_lang(123.into(), "w", "ko").unwrap(),
+            "백이십삼"
+        );
+        assert_eq!(
+            format_integer_lang(123.into(), "w;t", "ko").unwrap(),
+            "백스물셋"
+        );
+        assert_eq!(format_integer_lang(2.into(), "w;ot", "ko").unwrap(), "둘째");
+        assert_eq!(
+            format_integer_lang((-5).into(), "w", "ko").unwrap(),
+            "마이너스 오"
+        );
+        assert_eq!(format_integer_lang(3.into(), "1;o", "ko").unwrap(), "3번째");
+        // the traditional modifier only matters for Korean
+        assert_eq!(
+            format_integer_lang(23.into(), "w;t", "en").unwrap(),
+            "twenty-three"
+        );
+        assert_ne!(
+            Picture::parse("w").unwrap().fingerprint(),
+            Picture::parse("w;t").unwrap().fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_format_integer_swiss_german() {
+        assert_eq!(
+            format_integer_lang(31.into(), "w", "de-CH").unwrap(),
+            "einunddreissig"
+        );
+        assert_eq!(
+            format_integer_lang(30.into(), "W;o", "de-LI").unwrap(),
+            "DREISSIGSTE"
+        );
+        assert_eq!(
+            format_integer_lang(31.into(), "w", "de-AT").unwrap(),
+            "einunddreißig"
+        );
+        // the country chooses the words as the region of the language does
+        let options = FormatOptions::builder()
+            .language("de")
+            .country("ch")
+            .build()
+            .unwrap();
+        assert_eq!(
+            format_integer_with_options(30.into(), "w", &options).unwrap(),
+            "dreissig"
+        );
+    }
+
+    #[test]
+    fn test_format_integer_lang_falls_back_to_english() {
+        assert_eq!(format_integer_lang(3.into(), "w;o", "fr").unwrap(), "third");
+        // the words are cased as English, not by the Turkish rules
+        assert_eq!(format_integer_lang(9.into(), "W", "tr").unwrap(), "NINE");
+        assert_eq!(
+            format_integer_lang(3.into(), "w", "not a language"),
+            Err(Error::InvalidLanguageTag("not a language".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_sign_placeholders() {
+        let options = FormatOptions::builder().extensions(true).build().unwrap();
+        let format = |i: i64, picture| format_integer_with_options(i.into(), picture, &options);
+        assert_eq!(format(5, "+0").unwrap(), "+5");
+        assert_eq!(format(-5, "+0").unwrap(), "-5");
+        assert_eq!(format(0, "+0").unwrap(), "+0");
+        assert_eq!(format(5, "-#,##0").unwrap(), "5");
+        assert_eq!(format(-1234, "#,##0-").unwrap(), "1,234-");
+        assert_eq!(format(1234, "#,##0+").unwrap(), "1,234+");
+        // in between, they're still grouping separators
+        assert_eq!(format(-1234, "0-000").unwrap(), "-1-234");
+        // only one placeholder is taken
+        assert_eq!(format(5, "+0+"), Err(suggesting(invalid(2, '+'), "+0")));
+    }
+
+    #[test]
+    fn test_sign_placeholders_need_extensions() {
+        assert_eq!(Picture::parse("+0"), Err(suggesting(invalid(0, '+'), "0")));
+        assert_eq!(Picture::parse("0-"), Err(suggesting(invalid(1, '-'), "0")));
+    }
+
+    #[test]
+    fn test_sign_placeholder_output_len() {
+        let options = FormatOptions::builder()
+            .extensions(true)
+            .max_output_len(2)
+            .build()
+            .unwrap();
+        assert_eq!(
+            format_integer_with_options(5.into(), "0+", &options).unwrap(),
+            "5+"
+        );
+        assert_eq!(
+            format_integer_with_options(10.into(), "0+", &options),
+            Err(Error::OutputTooLong)
+        );
+    }
+
+    #[test]
+    fn test_zero_and_negative_numerals() {
+        // tokens whose numerals have no zero, which is written in decimal
+        for token in ["a", "A", "i", "I", "α", "א", "Ա", "ა", "፩", "௧;t", "i;o"] {
+            assert_eq!(format_integer(0.into(), token).unwrap(), "0", "{}", token);
+        }
+        // tokens whose numerals have one
+        assert_eq!(format_integer(0.into(), "①").unwrap(), "⓪");
+        assert_eq!(format_integer(0.into(), "一").unwrap(), "〇");
+        assert_eq!(format_integer(0.into(), "w").unwrap(), "zero");
+        // a negative value is a minus sign followed by its absolute value,
+        // whether or not the numerals can represent that
+        for token in [
+            "a", "A", "i", "I", "α", "א", "Ա", "ა", "①", "፩", "一", "壹", "௧;t",
+        ] {
+            for value in [1, 27, 5000, 100000] {
+                let positive = format_integer(value.into(), token).unwrap();
+                let negative = format_integer((-value).into(), token).unwrap();
+                assert_eq!(negative, format!("-{}", positive), "{}", token);
             }
         }
-
-        last_separator.map(|last_separator| Regular {
-            group_separator: last_separator,
-            count: last_count.unwrap(),
-            mandatory_digit_max: mandatory_digit_max_count,
-            digit_family,
-        })
+        assert_eq!(format_integer((-4).into(), "w").unwrap(), "minus four");
+        assert_eq!(format_integer((-4000).into(), "i").unwrap(), "-4000");
     }
 
-    fn signs(&self) -> Box<dyn Iterator<Item = Sign> + '_> {
-        match self {
-            Self::NonRegular(p) => Box::new(p.signs()),
-            Self::Regular(p) => Box::new(p.signs()),
-        }
+    #[test]
+    fn test_format_roman() {
+        assert_eq!(format_integer(1999.into(), "i").unwrap(), "mcmxcix");
+        assert_eq!(format_integer((-4).into(), "i").unwrap(), "-iv");
+        // values without a Roman numeral are decimal
+        assert_eq!(format_integer(0.into(), "i").unwrap(), "0");
+        assert_eq!(format_integer(4000.into(), "i").unwrap(), "4000");
+        assert!(!Picture::parse("i").unwrap().sortable());
     }
 
-    fn mandatory_digit_max(&self) -> usize {
-        match self {
-            Self::NonRegular(p) => p.mandatory_digit_max(),
-            Self::Regular(p) => p.mandatory_digit_max(),
-        }
+    #[test]
+    fn test_format_roman_overflow() {
+        let format = |value: i32, overflow| {
+            let options = FormatOptions::builder()
+                .roman_overflow(overflow)
+                .build()
+                .unwrap();
+            format_integer_with_options(value.into(), "I", &options).unwrap()
+        };
+        assert_eq!(format(4420, RomanOverflow::Decimal), "4420");
+        assert_eq!(format(4420, RomanOverflow::RepeatM(5000)), "MMMMCDXX");
+        assert_eq!(format(5001, RomanOverflow::RepeatM(5000)), "5001");
+        assert_eq!(
+            format(-4420, RomanOverflow::Overline),
+            "-I\u{305}V\u{305}CDXX"
+        );
+        assert_eq!(format(4_000_000, RomanOverflow::Overline), "4000000");
     }
 
-    fn digit_family(&self) -> Option<DigitFamily> {
-        match self {
-            Self::NonRegular(p) => p.digit_family,
-            Self::Regular(p) => p.digit_family,
+    #[test]
+    fn test_roman_overflow_max_formatted_len() {
+        for (value, overflow) in [
+            (-3_888_888, RomanOverflow::Overline),
+            (-9888, RomanOverflow::RepeatM(9999)),
+            (-65535, RomanOverflow::RepeatM(u16::MAX)),
+            (-12_345_678, RomanOverflow::Overline),
+        ] {
+            let options = FormatOptions::builder()
+                .roman_overflow(overflow)
+                .build()
+                .unwrap();
+            let picture = Picture::parse_with_options("I", &options).unwrap();
+            let digit_count = value.to_string().len() - 1;
+            let len = picture.format(&value.into()).len();
+            assert!(len <= picture.max_formatted_len(digit_count), "{}", value);
+            let default = Picture::parse("I").unwrap();
+            assert_eq!(
+                default.max_formatted_len_with_options(digit_count, &options),
+                picture.max_formatted_len(digit_count)
+            );
         }
     }
-}
 
-#[derive(Debug, PartialEq)]
-struct Picture {
-    pattern: Pattern,
-}
+    #[test]
+    fn test_roman_number_forms_max_formatted_len() {
+        let options = FormatOptions::builder()
+            .roman_number_forms(true)
+            .build()
+            .unwrap();
+        let picture = Picture::parse_with_options("I", &options).unwrap();
+        let output = picture.format(&(-3888).into());
+        assert_eq!(output.len(), 46);
+        assert!(output.len() <= picture.max_formatted_len(4));
+        let mut buffer = String::with_capacity(picture.max_formatted_len(4));
+        let capacity = buffer.capacity();
+        picture.format_into(&(-3888).into(), &mut buffer).unwrap();
+        assert_eq!(buffer.capacity(), capacity);
+    }
 
-impl Picture {
-    fn parse(picture: &str) -> Result<Self, Error> {
-        Ok(Self {
-            pattern: Pattern::new(picture)?,
-        })
+    #[test]
+    fn test_format_roman_number_forms() {
+        let options = FormatOptions::builder()
+            .roman_number_forms(true)
+            .build()
+            .unwrap();
+        let format = |value: i32, picture| {
+            format_integer_with_options(value.into(), picture, &options).unwrap()
+        };
+        assert_eq!(format(4, "i"), "ⅳ");
+        assert_eq!(format(12, "I"), "Ⅻ");
+        assert_eq!(format(1999, "I"), "ⅯⅭⅯⅩⅭⅠⅩ");
+        assert_eq!(format(-14, "i"), "-ⅹⅰⅴ");
+        // values without a numeral are still decimal
+        assert_eq!(format(0, "I"), "0");
     }
 
-    fn format(&self, i: IBig) -> String {
-        let is_negative = i.is_negative();
-        let i = i.abs();
+    #[test]
+    fn test_format_roman_upper_case() {
+        assert_eq!(format_integer(2024.into(), "I").unwrap(), "MMXXIV");
+        assert_eq!(format_integer((-9).into(), "I").unwrap(), "-IX");
+        // beyond the conventional range we fall back to decimal, as for
+        // lower case
+        assert_eq!(format_integer(0.into(), "I").unwrap(), "0");
+        assert_eq!(format_integer(12345.into(), "I").unwrap(), "12345");
+        assert_ne!(
+            Picture::parse("i").unwrap().fingerprint(),
+            Picture::parse("I").unwrap().fingerprint()
+        );
+    }
 
-        // turn the integer into a string of digits
-        let s = i.to_string();
+    #[test]
+    fn test_format_alphabetic() {
+        assert_eq!(format_integer(1.into(), "a").unwrap(), "a");
+        assert_eq!(format_integer(26.into(), "a").unwrap(), "z");
+        assert_eq!(format_integer(27.into(), "a").unwrap(), "aa");
+        assert_eq!(format_integer((-2).into(), "a").unwrap(), "-b");
+        // there's no letter for zero
+        assert_eq!(format_integer(0.into(), "a").unwrap(), "0");
+        let picture = Picture::parse("a").unwrap();
+        assert_eq!(picture.max_formatted_len(3), 4);
+    }
 
-        // the amount of zeros we want to produce is the amount of
-        // mandatory digits minus the digits we already produce
-        let zeros_amount = self
-            .pattern
-            .mandatory_digit_max()
-            .saturating_sub(s.chars().count());
-
-        // an iterator that produces the zeros we want to pad with
-        let mut zero_count = 0;
-        let zeros = std::iter::from_fn(|| {
-            if zero_count < zeros_amount {
-                zero_count += 1;
-                Some('0')
-            } else {
-                None
-            }
-        });
+    #[test]
+    fn test_format_alphabetic_upper() {
+        assert_eq!(format_integer(1.into(), "A").unwrap(), "A");
+        assert_eq!(format_integer(28.into(), "A").unwrap(), "AB");
+        assert_eq!(format_integer(703.into(), "A").unwrap(), "AAA");
+        assert_eq!(format_integer(0.into(), "A").unwrap(), "0");
+        assert_ne!(
+            Picture::parse("a").unwrap().fingerprint(),
+            Picture::parse("A").unwrap().fingerprint()
+        );
+    }
 
-        // an iterator over the digit chars
-        let digits = s.chars().rev();
+    #[test]
+    fn test_format_hebrew() {
+        assert_eq!(format_integer(16.into(), "א").unwrap(), "ט״ז");
+        assert_eq!(format_integer(5784.into(), "א").unwrap(), "ה׳תשפ״ד");
+        assert_eq!(format_integer((-3).into(), "א").unwrap(), "-ג׳");
+        // values without a Hebrew numeral are decimal
+        assert_eq!(format_integer(0.into(), "א").unwrap(), "0");
+        assert_eq!(format_integer(6000.into(), "א").unwrap(), "6000");
+        let picture = Picture::parse("א").unwrap();
+        assert_eq!(picture.max_formatted_len(4), 17);
+        assert!(!picture.sortable());
+    }
 
-        // the zero padding comes after the proper digits
-        let mut digits = digits.chain(zeros).peekable();
+    #[test]
+    fn test_format_armenian() {
+        assert_eq!(format_integer(1999.into(), "Ա").unwrap(), "ՌՋՂԹ");
+        assert_eq!(format_integer((-12).into(), "Ա").unwrap(), "-ԺԲ");
+        assert_eq!(format_integer(10_000.into(), "Ա").unwrap(), "10000");
+        let picture = Picture::parse("Ա").unwrap();
+        assert_eq!(picture.max_formatted_len(4), 9);
+        assert_ne!(
+            picture.fingerprint(),
+            Picture::parse("א").unwrap().fingerprint()
+        );
+    }
 
-        // we either need to add a negative sign in the end or no sign
-        let negative_vec = if is_negative { vec!['-'] } else { vec![] };
+    #[test]
+    fn test_format_georgian() {
+        assert_eq!(format_integer(2024.into(), "ა").unwrap(), "ცკდ");
+        assert_eq!(format_integer(19_999.into(), "ა").unwrap(), "ჵჰშჟთ");
+        assert_eq!(format_integer(20_000.into(), "ა").unwrap(), "20000");
+    }
 
-        // for as many digit as we have, we take a sign (of which we get an
-        // infinite supply for both regular and non-regular), and process them
-        // accordingly
-        let mut signs = self.pattern.signs();
-        let digit_family = self.pattern.digit_family();
+    #[test]
+    fn test_format_circled() {
+        assert_eq!(format_integer(1.into(), "①").unwrap(), "①");
+        assert_eq!(format_integer(20.into(), "①").unwrap(), "⑳");
+        assert_eq!(format_integer(42.into(), "①").unwrap(), "㊷");
+        assert_eq!(format_integer(0.into(), "①").unwrap(), "⓪");
+        assert_eq!(format_integer((-3).into(), "①").unwrap(), "-③");
+        // beyond the circled numbers, values are decimal
+        assert_eq!(format_integer(51.into(), "①").unwrap(), "51");
+        assert_eq!(Picture::parse("①").unwrap().max_formatted_len(2), 4);
+    }
 
-        let output = std::iter::from_fn(|| {
-            signs.next().and_then(|sign| match sign {
-                Sign::OptionalDigit | Sign::MandatoryDigit => {
-                    let digit = digits.next()?;
-                    let digit = if let Some(digit_family) = digit_family {
-                        digit_family.digit(AsciiDigit::new(digit))
-                    } else {
-                        digit
-                    };
-                    Some(digit)
-                }
-                Sign::GroupSeparator(c) => {
-                    if digits.peek().is_none() {
-                        None
-                    } else {
-                        Some(c)
-                    }
-                }
-            })
-        })
-        .chain(negative_vec);
+    #[test]
+    fn test_format_tamil() {
+        assert_eq!(format_integer(2315.into(), "௧;t").unwrap(), "௨௲௩௱௰௫");
+        assert_eq!(format_integer(10.into(), "௧;ct").unwrap(), "௰");
+        // without the modifier, they're decimal digits
+        assert_eq!(format_integer(10.into(), "௧").unwrap(), "௧௦");
+        assert_eq!(format_integer(10.into(), "௧;a").unwrap(), "௧௦");
+        assert_eq!(format_integer(10_000.into(), "௧;t").unwrap(), "10000");
+    }
 
-        let mut output = output.collect::<Vec<_>>();
-        output.reverse();
-        output.iter().collect()
+    #[test]
+    fn test_format_ethiopic() {
+        assert_eq!(format_integer(123_456.into(), "፩").unwrap(), "፲፪፼፴፬፻፶፮");
+        assert_eq!(format_integer((-100).into(), "፩").unwrap(), "-፻");
+        assert_eq!(format_integer(0.into(), "፩").unwrap(), "0");
+        let picture = Picture::parse("፩").unwrap();
+        assert_eq!(picture.max_formatted_len(6), 28);
     }
-}
 
-pub fn format_integer(i: IBig, picture: &str) -> Result<String, Error> {
-    let picture = Picture::parse(picture)?;
-    Ok(picture.format(i))
-}
+    #[test]
+    fn test_format_kanji() {
+        assert_eq!(format_integer(1234.into(), "一").unwrap(), "千二百三十四");
+        assert_eq!(format_integer(20_000.into(), "一").unwrap(), "二万");
+        assert_eq!(format_integer(0.into(), "一").unwrap(), "〇");
+        assert_eq!(format_integer((-7).into(), "一").unwrap(), "-七");
+        let large: IBig = format!("1{}", "0".repeat(20)).parse().unwrap();
+        assert_eq!(
+            format_integer(large.clone(), "一").unwrap(),
+            large.to_string()
+        );
+        assert!(!Picture::parse("一").unwrap().sortable());
+    }
 
-#[derive(Debug, PartialEq)]
-pub enum Error {
-    InvalidPictureString,
-}
+    #[test]
+    fn test_format_financial() {
+        assert_eq!(format_integer(1234.into(), "壹").unwrap(), "壹仟貳佰參拾肆");
+        assert_eq!(format_integer(1001.into(), "壹").unwrap(), "壹仟零壹");
+        assert_ne!(
+            Picture::parse("壹").unwrap().fingerprint(),
+            Picture::parse("一").unwrap().fingerprint()
+        );
+    }
 
-#[cfg(test)]
-mod tests {
+    #[test]
+    fn test_format_greek() {
+        assert_eq!(format_integer(3.into(), "α").unwrap(), "γ");
+        assert_eq!(format_integer(26.into(), "α").unwrap(), "αβ");
+        assert_eq!(format_integer(18.into(), "Α").unwrap(), "Σ");
+        assert_eq!(format_integer(0.into(), "α").unwrap(), "0");
+        let picture = Picture::parse("Α").unwrap();
+        assert_eq!(picture.max_formatted_len(3), 7);
+        assert!(picture.sortable());
+        assert_ne!(
+            picture.fingerprint(),
+            Picture::parse("A").unwrap().fingerprint()
+        );
+    }
 
-    use super::*;
+    #[test]
+    fn test_roman_max_formatted_len() {
+        let picture = Picture::parse("i").unwrap();
+        assert_eq!(picture.max_formatted_len(4), 16);
+        assert_eq!(picture.max_formatted_len(20), 21);
+    }
 
     #[test]
-    fn test_format_integer() {
-        assert_eq!(format_integer(123.into(), "1").unwrap(), "123");
+    fn test_scaling() {
+        let options = FormatOptions::builder().extensions(true).build().unwrap();
+        let format = |i: i64, picture| format_integer_with_options(i.into(), picture, &options);
+        assert_eq!(format(1_234_567, "#,##0,").unwrap(), "1,235");
+        assert_eq!(format(1_234_567, "#,##0,,").unwrap(), "1");
+        assert_eq!(format(-1_234_567, "#,##0,").unwrap(), "-1,235");
+        assert_eq!(format(-400, "0,").unwrap(), "0");
+        assert_eq!(format(999_500, "0,").unwrap(), "1000");
+        assert_eq!(format(5, "0,,,").unwrap(), "0");
+        // with a sign placeholder after the scaling
+        assert_eq!(format(-12_000, "0,-").unwrap(), "12-");
+        // without extensions, trailing separators are invalid
+        assert_eq!(
+            Picture::parse("#,##0,"),
+            Err(suggesting(invalid(5, ','), "#,##0"))
+        );
     }
 
     #[test]
-    fn test_format_zero_padded_integer() {
-        assert_eq!(format_integer(123.into(), "0000").unwrap(), "0123");
+    fn test_currency_placeholders() {
+        let options = FormatOptions::builder()
+            .extensions(true)
+            .currency_symbol("€")
+            .currency_code("eur")
+            .build()
+            .unwrap();
+        let format = |i: i64, picture| format_integer_with_options(i.into(), picture, &options);
+        assert_eq!(format(1234, "¤#,##0").unwrap(), "€1,234");
+        assert_eq!(format(-1234, "#,##0 ¤").unwrap(), "-1,234 €");
+        assert_eq!(format(1234, "¤¤\u{a0}0").unwrap(), "EUR\u{a0}1234");
+        // the sign goes outside the currency
+        assert_eq!(format(-5, "+¤0").unwrap(), "-€5");
+        assert_eq!(format(5, "0¤+").unwrap(), "5€+");
+        assert_eq!(format(1_234_567, "#,##0, ¤").unwrap(), "1,235 €");
+        // without extensions, a currency sign is a grouping separator
+        assert_eq!(Picture::parse("¤0"), Err(suggesting(invalid(0, '¤'), "0")));
     }
 
     #[test]
-    fn test_format_zero_padded_integer_negative() {
-        assert_eq!(format_integer((-123).into(), "00000").unwrap(), "-00123");
+    fn test_currency_placeholder_fallback() {
+        let code_only = FormatOptions::builder()
+            .extensions(true)
+            .currency_code("chf")
+            .build()
+            .unwrap();
+        assert_eq!(
+            format_integer_with_options(5.into(), "¤ 0", &code_only).unwrap(),
+            "CHF 5"
+        );
+        let neither = FormatOptions::builder().extensions(true).build().unwrap();
+        assert_eq!(
+            format_integer_with_options(5.into(), "0¤¤", &neither).unwrap(),
+            "5¤¤"
+        );
     }
 
     #[test]
-    fn test_format_zero_padded_integer_negative_shorter() {
-        assert_eq!(format_integer((-123).into(), "0000").unwrap(), "-0123");
+    fn test_currency_placeholder_chunks_and_len() {
+        let options = FormatOptions::builder()
+            .extensions(true)
+            .currency_symbol("€")
+            .build()
+            .unwrap();
+        let picture = Picture::parse_with_options("#,##0 ¤", &options).unwrap();
+        let mut chunks: Vec<(ChunkKind, String)> = Vec::new();
+        picture.format_to_sink(&1234.into(), &mut chunks);
+        assert_eq!(
+            chunks.last().unwrap(),
+            &(ChunkKind::Currency, " €".to_string())
+        );
+        // " €" takes four bytes
+        assert_eq!(picture.max_formatted_len(4), 10);
+        assert_ne!(
+            picture.fingerprint(),
+            Picture::parse_with_options("#,##0", &options)
+                .unwrap()
+                .fingerprint()
+        );
     }
 
     #[test]
-    fn test_format_with_thousands_separator() {
-        assert_eq!(format_integer(1234.into(), "0,000").unwrap(), "1,234");
+    fn test_accounting() {
+        let options = FormatOptions::builder().accounting(true).build().unwrap();
+        let format = |i: i64, picture| format_integer_with_options(i.into(), picture, &options);
+        assert_eq!(format(-1234, "#,##0").unwrap(), "(1,234)");
+        assert_eq!(format(1234, "#,##0").unwrap(), "1,234");
+        assert_eq!(format(-4, "i").unwrap(), "(iv)");
+        assert_eq!(format(-21, "w").unwrap(), "(twenty-one)");
+        assert_eq!(format(0, "0").unwrap(), "0");
+        let picture = Picture::parse_with_options("#,##0", &options).unwrap();
+        let mut chunks: Vec<(ChunkKind, String)> = Vec::new();
+        picture.format_to_sink(&(-5).into(), &mut chunks);
+        assert_eq!(
+            chunks,
+            vec![
+                (ChunkKind::Sign, "(".to_string()),
+                (ChunkKind::Digits, "5".to_string()),
+                (ChunkKind::Sign, ")".to_string()),
+            ]
+        );
+        assert_eq!(
+            picture.max_formatted_len(4),
+            Picture::parse("#,##0").unwrap().max_formatted_len(4) + 1
+        );
     }
 
     #[test]
-    fn test_format_with_thousands_separator2() {
-        assert_eq!(format_integer(4321.into(), "0,000").unwrap(), "4,321");
+    fn test_accounting_output_len() {
+        let options = FormatOptions::builder()
+            .accounting(true)
+            .max_output_len(3)
+            .build()
+            .unwrap();
+        assert_eq!(
+            format_integer_with_options((-5).into(), "0", &options).unwrap(),
+            "(5)"
+        );
+        assert_eq!(
+            format_integer_with_options((-50).into(), "0", &options),
+            Err(Error::OutputTooLong)
+        );
     }
 
     #[test]
-    fn test_format_with_thousands_separator_and_zero_prefix() {
-        assert_eq!(format_integer(4321.into(), "00,000").unwrap(), "04,321");
+    fn test_accounting_with_sign_placeholder() {
+        let options = FormatOptions::builder()
+            .extensions(true)
+            .accounting(true)
+            .build()
+            .unwrap();
+        let format = |i: i64, picture| format_integer_with_options(i.into(), picture, &options);
+        assert_eq!(format(-5, "+0").unwrap(), "(5)");
+        assert_eq!(format(-5, "0+").unwrap(), "(5)");
+        assert_eq!(format(5, "+0").unwrap(), "+5");
+        for picture in ["+0", "0+", "-0"] {
+            let picture = Picture::parse_with_options(picture, &options).unwrap();
+            for i in [-5, 5, -12345] {
+                let i = IBig::from(i);
+                let len = picture.format(&i).len();
+                assert_eq!(pattern_output_len(&picture, &i, &options), Some(len));
+                assert!(len <= picture.max_formatted_len(5));
+                assert!(len <= picture.max_width(5));
+            }
+        }
+        let limited = FormatOptions::builder()
+            .extensions(true)
+            .accounting(true)
+            .max_output_len(3)
+            .build()
+            .unwrap();
+        assert_eq!(
+            format_integer_with_options((-5).into(), "+0", &limited).unwrap(),
+            "(5)"
+        );
+        assert_eq!(
+            format_integer_with_options((-50).into(), "+0", &limited),
+            Err(Error::OutputTooLong)
+        );
     }
 
     #[test]
-    fn test_illegal_primary_token() {
-        assert_eq!(Picture::parse("0b0"), Err(Error::InvalidPictureString));
+    fn test_accounting_at_format_time() {
+        let accounting = FormatOptions::builder().accounting(true).build().unwrap();
+        let picture = Picture::parse("0").unwrap();
+        assert_eq!(
+            picture
+                .format_with_options((-5).into(), &accounting)
+                .unwrap(),
+            "(5)"
+        );
+        let picture = Picture::parse_with_options("0", &accounting).unwrap();
+        assert_eq!(picture.format(&(-5).into()), "(5)");
+        assert_eq!(
+            picture
+                .format_with_options((-5).into(), &FormatOptions::default())
+                .unwrap(),
+            "-5"
+        );
     }
 
     #[test]
-    fn test_illegal_decimal_digit_pattern_with_adjacent_grouping_separators() {
-        assert_eq!(Picture::parse("0,,0"), Err(Error::InvalidPictureString));
+    fn test_accounting_scaled_to_zero() {
+        let options = FormatOptions::builder()
+            .extensions(true)
+            .accounting(true)
+            .build()
+            .unwrap();
+        assert_eq!(
+            format_integer_with_options((-400).into(), "0,", &options).unwrap(),
+            "0"
+        );
+        assert_eq!(
+            format_integer_with_options((-1500).into(), "0,", &options).unwrap(),
+            "(2)"
+        );
     }
 
     #[test]
-    fn test_illegal_decimal_digit_pattern_with_starting_grouping_separator() {
-        assert_eq!(Picture::parse(",0"), Err(Error::InvalidPictureString));
+    fn test_scale_digits_rounds_half_to_even() {
+        assert_eq!(scale_digits("1500", 1), "2");
+        assert_eq!(scale_digits("2500", 1), "2");
+        assert_eq!(scale_digits("2501", 1), "3");
+        assert_eq!(scale_digits("2499", 1), "2");
+        assert_eq!(scale_digits("500", 1), "0");
+        assert_eq!(scale_digits("501", 1), "1");
+        assert_eq!(scale_digits("99", 1), "0");
+        assert_eq!(scale_digits("9999999", 2), "10");
     }
 
     #[test]
-    fn test_illegal_decimal_digit_pattern_with_ending_grouping_separator() {
-        assert_eq!(Picture::parse("0,"), Err(Error::InvalidPictureString));
+    fn test_unsupported_numbering_sequence_falls_back() {
+        assert_eq!(format_integer(12.into(), "z").unwrap(), "12");
+        assert_eq!(format_integer(12.into(), "Ⅰ").unwrap(), "12");
     }
 
     #[test]
-    fn test_optional_digit_sign_before_mandatory_digit_sign() {
-        assert!(Picture::parse("#0").is_ok());
+    fn test_unsupported_numbering_sequence_strict() {
+        let options = FormatOptions::builder().strict(true).build().unwrap();
+        assert_eq!(
+            format_integer_with_options(12.into(), "z", &options),
+            Err(Error::Unsupported(Feature::NumberingSequence(
+                "z".to_string()
+            )))
+        );
+        // an invalid decimal digit pattern is still invalid
+        assert_eq!(
+            format_integer_with_options(12.into(), "0b0", &options),
+            Err(invalid(1, 'b'))
+        );
     }
 
     #[test]
-    fn test_optional_digit_by_itself_is_illegal() {
-        assert_eq!(Picture::parse("#"), Err(Error::InvalidPictureString));
+    fn test_optional_digits_only_is_illegal() {
+        // there must be at least one mandatory digit sign
+        assert_eq!(Picture::parse("##"), Err(suggesting(invalid(1, '#'), "#0")));
+        assert_eq!(
+            Picture::parse("#,#"),
+            Err(suggesting(invalid(2, '#'), "#,0"))
+        );
     }
 
     #[test]
-    fn test_optional_digit_sign_after_mandatory_digit_sign_is_illegal() {
-        assert_eq!(Picture::parse("0#0"), Err(Error::InvalidPictureString));
+    fn test_optional_digit_followed_by_separator() {
+        // an optional digit may be followed by a separator as long as a
+        // digit follows
+        assert!(Picture::parse("#,0").is_ok());
+        assert!(Picture::parse("#,#0").is_ok());
+        assert_eq!(Picture::parse("#,"), Err(suggesting(invalid(1, ','), "0")));
+        assert_eq!(
+            Picture::parse("#,,0"),
+            Err(suggesting(invalid(2, ','), "#,0"))
+        );
     }
 
     #[test]
-    fn test_optional_digit_sign_after_grouping_separator_and_mandatory_digit_sign_is_illegal() {
-        assert_eq!(Picture::parse("0,#0"), Err(Error::InvalidPictureString));
+    fn test_optional_digit_at_end_is_illegal() {
+        assert_eq!(
+            Picture::parse("0,0#"),
+            Err(suggesting(invalid(3, '#'), "#,00"))
+        );
     }
 
     #[test]
@@ -424,7 +2713,7 @@ mod tests {
     #[test]
     fn test_digits_not_in_same_digit_family_is_illegal() {
         // here we mix arab indic digit family with ascii digits
-        assert_eq!(Picture::parse("0٠"), Err(Error::InvalidPictureString));
+        assert_eq!(Picture::parse("0٠"), Err(invalid(1, '٠')));
     }
 
     #[test]
@@ -436,7 +2725,7 @@ mod tests {
     fn test_reject_illegal_separator() {
         // the roman numeral I is in the digit family Nl, which is not
         // a valid separator
-        assert_eq!(Picture::parse("0Ⅰ0"), Err(Error::InvalidPictureString));
+        assert_eq!(Picture::parse("0Ⅰ0"), Err(invalid(1, 'Ⅰ')));
     }
 
     #[test]
@@ -487,6 +2776,27 @@ mod tests {
         assert_eq!(format_integer(15.into(), "߀").unwrap(), "߁߅")
     }
 
+    #[test]
+    fn test_format_round_trips_in_every_digit_family() {
+        let gc = icu::properties::maps::general_category();
+        let value: IBig = "9876543210".parse().unwrap();
+        for r in gc.iter_ranges_for_value(icu::properties::GeneralCategory::DecimalNumber) {
+            for zero in r.step_by(10) {
+                // a picture in this family's digits, read back as ascii
+                let picture = format!("#,##{}", char::from_u32(zero).unwrap());
+                let formatted = format_integer(value.clone(), &picture).unwrap();
+                let ascii: String = formatted
+                    .chars()
+                    .map(|c| match c {
+                        ',' => ',',
+                        c => char::from_digit(c as u32 - zero, 10).unwrap(),
+                    })
+                    .collect();
+                assert_eq!(ascii, "9,876,543,210", "zero {:X}", zero);
+            }
+        }
+    }
+
     #[test]
     fn test_format_with_only_optional_digits() {
         assert_eq!(format_integer(15.into(), "#1").unwrap(), "15");
@@ -497,6 +2807,372 @@ mod tests {
         assert_eq!(format_integer(15453.into(), "#,##1").unwrap(), "15,453");
     }
 
+    #[test]
+    fn test_format_integer_value() {
+        assert_eq!(format_integer_value(&1234i64, "#,##0").unwrap(), "1,234");
+        assert_eq!(
+            format_integer_value(&i64::MIN, "0").unwrap(),
+            "-9223372036854775808"
+        );
+        assert_eq!(format_integer_value(&7u8, "w").unwrap(), "seven");
+        assert_eq!(
+            format_integer_value(&1i64, "#"),
+            Err(suggesting(invalid(0, '#'), "0"))
+        );
+    }
+
+    #[test]
+    fn test_format_value() {
+        let picture = Picture::parse("i").unwrap();
+        assert_eq!(picture.format_value(&2024u32), "mmxxiv");
+        assert_eq!(picture.format_value(&-4i32), "-iv");
+    }
+
+    #[test]
+    fn test_format_into() {
+        let picture = Picture::parse("#,##0").unwrap();
+        let mut output = "total: ".to_string();
+        picture.format_into(&1234.into(), &mut output).unwrap();
+        assert_eq!(output, "total: 1,234");
+        output.clear();
+        picture.format_into(&(-5).into(), &mut output).unwrap();
+        assert_eq!(output, "-5");
+    }
+
+    #[test]
+    fn test_format_into_passes_on_errors() {
+        struct Failing(usize);
+        impl fmt::Write for Failing {
+            fn write_str(&mut self, _s: &str) -> fmt::Result {
+                self.0 += 1;
+                Err(fmt::Error)
+            }
+        }
+        let picture = Picture::parse("#,##0").unwrap();
+        let mut out = Failing(0);
+        assert_eq!(
+            picture.format_into(&1234567.into(), &mut out),
+            Err(fmt::Error)
+        );
+        // nothing is written after the first error
+        assert_eq!(out.0, 1);
+    }
+
+    #[test]
+    fn test_format_to_sink_chunks() {
+        let mut chunks = Vec::new();
+        Picture::parse("#,##0")
+            .unwrap()
+            .format_to_sink(&(-1234567).into(), &mut chunks);
+        assert_eq!(
+            chunks,
+            vec![
+                (ChunkKind::Sign, "-".to_string()),
+                (ChunkKind::Digits, "1".to_string()),
+                (ChunkKind::Separator, ",".to_string()),
+                (ChunkKind::Digits, "234".to_string()),
+                (ChunkKind::Separator, ",".to_string()),
+                (ChunkKind::Digits, "567".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_to_sink_chunks_padded() {
+        let mut chunks = Vec::new();
+        Picture::parse("00.000")
+            .unwrap()
+            .format_to_sink(&12.into(), &mut chunks);
+        assert_eq!(
+            chunks,
+            vec![
+                (ChunkKind::Digits, "00".to_string()),
+                (ChunkKind::Separator, ".".to_string()),
+                (ChunkKind::Digits, "012".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_panic_on_any_short_picture() {
+        // try every picture up to length 5 over an alphabet of interesting
+        // characters, formatting some interesting values with the valid ones
+        const ALPHABET: [char; 8] = ['#', '0', '9', ',', '.', 'a', '٠', ' '];
+        let values: Vec<IBig> = vec![
+            0.into(),
+            1.into(),
+            (-1).into(),
+            1_234_567.into(),
+            "-123456789012345678901234567890".parse().unwrap(),
+        ];
+        let mut valid = 0;
+        for length in 1..=5u32 {
+            for n in 0..ALPHABET.len().pow(length) {
+                let mut n = n;
+                let picture: String = (0..length)
+                    .map(|_| {
+                        let c = ALPHABET[n % ALPHABET.len()];
+                        n /= ALPHABET.len();
+                        c
+                    })
+                    .collect();
+                if let Ok(picture) = Picture::parse(&picture) {
+                    valid += 1;
+                    for value in &values {
+                        picture.format(value);
+                    }
+                }
+            }
+        }
+        assert!(valid > 0);
+    }
+
+    // a simple deterministic generator for property tests
+    fn pseudo_random_values(count: usize) -> Vec<IBig> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        (0..count)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                // spread the values over many magnitudes
+                IBig::from(state >> (state % 64))
+            })
+            .collect()
+    }
+
+    fn shortlex(a: &str, b: &str) -> std::cmp::Ordering {
+        a.chars()
+            .count()
+            .cmp(&b.chars().count())
+            .then_with(|| a.cmp(b))
+    }
+
+    #[test]
+    fn test_sortable_pictures_are_monotone() {
+        let mut values = pseudo_random_values(500);
+        values.extend((0..1100).map(IBig::from));
+        values.sort();
+        for picture in [
+            "0",
+            "0000",
+            "#,##0",
+            "0,000",
+            "#.##,##1",
+            "1,222.000",
+            "١",
+            "߀,߀߀߀",
+            "00.00",
+            "a",
+            "A",
+        ] {
+            let picture = Picture::parse(picture).unwrap();
+            assert!(picture.sortable());
+            let formatted = values.iter().map(|v| picture.format(v)).collect::<Vec<_>>();
+            for (pair, values) in formatted.windows(2).zip(values.windows(2)) {
+                assert_eq!(
+                    shortlex(&pair[0], &pair[1]),
+                    values[0].cmp(&values[1]),
+                    "{} {}",
+                    pair[0],
+                    pair[1]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_output_len() {
+        for (i, picture) in [
+            (IBig::from(0), "0"),
+            (IBig::from(-1234567), "#,##0"),
+            (IBig::from(12), "00.000"),
+            (IBig::from(-12345), "١,١"),
+            (IBig::from(1_222_333), "1,222.000"),
+            (IBig::from(-21), "#,##0;o"),
+            (IBig::from(12), "1;o"),
+        ] {
+            let picture = Picture::parse(picture).unwrap();
+            assert_eq!(
+                pattern_output_len(&picture, &i, &FormatOptions::default()),
+                Some(picture.format(&i).len())
+            );
+        }
+    }
+
+    #[test]
+    fn test_max_formatted_len() {
+        let picture = Picture::parse("#,##0").unwrap();
+        assert_eq!(picture.max_formatted_len(0), 2);
+        assert_eq!(picture.max_formatted_len(3), 4);
+        assert_eq!(picture.max_formatted_len(4), 6);
+        assert_eq!(picture.max_formatted_len(7), 10);
+        // mandatory digits are always there, and the grouping is regular
+        let picture = Picture::parse("00.000").unwrap();
+        assert_eq!(picture.max_formatted_len(1), 7);
+        assert_eq!(picture.max_formatted_len(7), 10);
+        // irregular grouping doesn't repeat
+        let picture = Picture::parse("1,222.000").unwrap();
+        assert_eq!(picture.max_formatted_len(9), 12);
+        // arab indic digits are two bytes each
+        let picture = Picture::parse("١").unwrap();
+        assert_eq!(picture.max_formatted_len(3), 7);
+    }
+
+    #[test]
+    fn test_max_formatted_len_fits_output() {
+        for picture in ["0", "#,##0", "1,222.000", "߀,߀߀߀", "00.00"] {
+            let picture = Picture::parse(picture).unwrap();
+            for i in [0, -1, 999, -1000, 1_234_567_890] {
+                let i = IBig::from(i);
+                let digit_count = i.to_string().trim_start_matches('-').len();
+                assert!(picture.format(&i).len() <= picture.max_formatted_len(digit_count));
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_output_len() {
+        let options = FormatOptions::builder().max_output_len(5).build().unwrap();
+        assert_eq!(
+            format_integer_with_options(1234.into(), "#,##0", &options).unwrap(),
+            "1,234"
+        );
+        assert_eq!(
+            format_integer_with_options((-1234).into(), "#,##0", &options),
+            Err(Error::OutputTooLong)
+        );
+    }
+
+    #[test]
+    fn test_max_output_len_huge_value() {
+        let options = FormatOptions::builder()
+            .max_output_len(100)
+            .build()
+            .unwrap();
+        let i = IBig::from(10).pow(1000);
+        assert_eq!(
+            format_integer_with_options(i, "0,0", &options),
+            Err(Error::OutputTooLong)
+        );
+    }
+
+    #[test]
+    fn test_format_compiled_picture() {
+        let picture = Picture::parse("#,##0").unwrap();
+        let formatted: Vec<String> = [1, 1234, -1_000_000]
+            .iter()
+            .map(|i| picture.format(&IBig::from(*i)))
+            .collect();
+        assert_eq!(formatted, ["1", "1,234", "-1,000,000"]);
+    }
+
+    #[test]
+    fn test_parse_chars() {
+        let chars = ['#', ',', '#', '#', '0'];
+        let picture = Picture::parse_chars(chars.iter().copied().enumerate()).unwrap();
+        assert_eq!(picture, Picture::parse("#,##0").unwrap());
+        assert_eq!(picture.format(&1234.into()), "1,234");
+    }
+
+    #[test]
+    fn test_suggestion() {
+        let suggestion = |picture| match Picture::parse(picture) {
+            Err(Error::InvalidPictureString(error)) => error.suggestion().map(str::to_string),
+            result => panic!("{:?}", result),
+        };
+        // optional digits after mandatory ones move to the front
+        assert_eq!(suggestion("0,##0#").as_deref(), Some("#,##00"));
+        assert_eq!(suggestion("##,#").as_deref(), Some("##,0"));
+        // the rest of the picture is kept
+        assert_eq!(suggestion("0,;o").as_deref(), Some("0;o"));
+        assert_eq!(suggestion("١,,٢").as_deref(), Some("١,٢"));
+        // we don't guess at other mistakes
+        assert_eq!(suggestion("0b0"), None);
+        assert_eq!(suggestion("١2"), None);
+        assert_eq!(suggestion(""), None);
+    }
+
+    #[test]
+    fn test_suggestion_with_extensions() {
+        let options = FormatOptions::builder().extensions(true).build().unwrap();
+        match Picture::parse_with_options("+0#0", &options) {
+            Err(Error::InvalidPictureString(error)) => {
+                assert_eq!(error.position(), 2);
+                assert_eq!(error.suggestion(), Some("+#00"));
+            }
+            result => panic!("{:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_parse_chars_invalid() {
+        let chars = ['0', ',', ',', '0'];
+        assert_eq!(
+            Picture::parse_chars(chars.iter().copied().enumerate()),
+            Err(suggesting(invalid(2, ','), "0,0"))
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        // this value must never change between runs or releases
+        assert_eq!(
+            Picture::parse("#,##0").unwrap().fingerprint(),
+            0x7277_f077_7cc3_2a23
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_same_compiled_picture() {
+        assert_eq!(
+            Picture::parse("#,##0").unwrap().fingerprint(),
+            Picture::parse("#,##1").unwrap().fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_differs() {
+        let fingerprints = ["0", "00", "0,000", "0.000", "0,00", "1,222.000", "١", "#0"]
+            .iter()
+            .map(|p| Picture::parse(p).unwrap().fingerprint())
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(fingerprints.len(), 8);
+    }
+
+    #[test]
+    fn test_fingerprint_options() {
+        let fingerprint = |options: crate::options::FormatOptionsBuilder| {
+            Picture::parse_with_options("w", &options.build().unwrap())
+                .unwrap()
+                .fingerprint()
+        };
+        let builder = FormatOptions::builder;
+        let fingerprints = [
+            builder(),
+            builder().language("de"),
+            builder().language("de-CH"),
+            builder().country("ch"),
+            builder().digit_family('٠'),
+            builder().accounting(true),
+            builder().nfc(true),
+            builder().roman_overflow(RomanOverflow::RepeatM(9999)),
+            builder().roman_overflow(RomanOverflow::RepeatM(5000)),
+            builder().roman_overflow(RomanOverflow::Overline),
+            builder().roman_number_forms(true),
+            builder().number_speller("cy", Welsh),
+        ]
+        .into_iter()
+        .map(fingerprint)
+        .collect::<std::collections::HashSet<_>>();
+        assert_eq!(fingerprints.len(), 12);
+        // options that don't change the output don't change the fingerprint
+        assert_eq!(
+            fingerprint(builder().max_output_len(10)),
+            Picture::parse("w").unwrap().fingerprint()
+        );
+    }
+
     #[test]
     fn test_format_with_million_and_thousand_separator() {
         assert_eq!(
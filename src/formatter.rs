@@ -0,0 +1,155 @@
+use ibig::IBig;
+
+use crate::error::Error;
+use crate::format_integer::{nfc_from, Picture};
+use crate::integer::{with_digits, Integer};
+use crate::options::FormatOptions;
+
+// Formats values into buffers it owns and reuses, so that formatting many
+// values in a loop doesn't allocate once the buffers have grown large
+// enough. The result borrows from the formatter, so it has to be used
+// before the next value is formatted.
+#[derive(Debug, Default)]
+pub struct Formatter {
+    digits: String,
+    scratch: String,
+    output: String,
+}
+
+impl Formatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // format with the options the picture was parsed with
+    pub fn format(&mut self, picture: &Picture, i: &IBig) -> &str {
+        self.format_value(picture, i)
+    }
+
+    // as format, but for any integer type. Unsigned types skip dealing
+    // with a sign altogether.
+    pub fn format_value<I: Integer + ?Sized>(&mut self, picture: &Picture, i: &I) -> &str {
+        self.format_value_with_options(picture, i, picture.options())
+    }
+
+    // as format, but with other options, like another language. As with
+    // Picture::format, there's no limit on the length of the output.
+    pub fn format_with_options(
+        &mut self,
+        picture: &Picture,
+        i: &IBig,
+        options: &FormatOptions,
+    ) -> &str {
+        self.format_value_with_options(picture, i, options)
+    }
+
+    // as format_with_options, but with the options' limit on the output
+    // length
+    pub(crate) fn format_checked(
+        &mut self,
+        picture: &Picture,
+        i: &IBig,
+        options: &FormatOptions,
+    ) -> Result<&str, Error> {
+        let Self {
+            digits,
+            scratch,
+            output,
+        } = self;
+        output.clear();
+        with_digits(i, digits, |is_negative, digits| {
+            picture.format_digits_with_options(is_negative, digits, options, scratch, output)
+        })?;
+        Ok(output)
+    }
+
+    fn format_value_with_options<I: Integer + ?Sized>(
+        &mut self,
+        picture: &Picture,
+        i: &I,
+        options: &FormatOptions,
+    ) -> &str {
+        let Self {
+            digits,
+            scratch,
+            output,
+        } = self;
+        output.clear();
+        // values too large for the stack are written to digits
+        with_digits(i, digits, |is_negative, digits| {
+            picture.format_digits_to_sink(is_negative, digits, options, scratch, output)
+        });
+        if options.nfc() {
+            nfc_from(&mut self.output, 0);
+        }
+        &self.output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        let picture = Picture::parse("#,##0").unwrap();
+        let mut formatter = Formatter::new();
+        assert_eq!(formatter.format(&picture, &1234.into()), "1,234");
+        assert_eq!(formatter.format(&picture, &(-5).into()), "-5");
+        assert_eq!(formatter.format(&picture, &1_000_000.into()), "1,000,000");
+    }
+
+    #[test]
+    fn test_format_different_pictures() {
+        let mut formatter = Formatter::new();
+        let padded = Picture::parse("0000").unwrap();
+        let arab_indic = Picture::parse("١").unwrap();
+        assert_eq!(formatter.format(&padded, &12.into()), "0012");
+        assert_eq!(formatter.format(&arab_indic, &(-12).into()), "-١٢");
+        assert_eq!(formatter.format(&padded, &(-1).into()), "-0001");
+    }
+
+    #[test]
+    fn test_format_reuses_buffer() {
+        let picture = Picture::parse("0").unwrap();
+        let mut formatter = Formatter::new();
+        formatter.format(&picture, &123456789.into());
+        let capacity = formatter.output.capacity();
+        formatter.format(&picture, &1.into());
+        formatter.format(&picture, &987654321.into());
+        assert_eq!(formatter.output.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_format_with_options() {
+        let german = FormatOptions::builder().language("de").build().unwrap();
+        let picture = Picture::parse("w").unwrap();
+        let mut formatter = Formatter::new();
+        assert_eq!(
+            formatter.format_with_options(&picture, &3.into(), &german),
+            "drei"
+        );
+        assert_eq!(formatter.format(&picture, &3.into()), "three");
+        // pictures keep the options they were parsed with
+        let picture = Picture::parse_with_options("w", &german).unwrap();
+        assert_eq!(formatter.format(&picture, &3.into()), "drei");
+        let arab_indic = FormatOptions::builder().digit_family('٠').build().unwrap();
+        let picture = Picture::parse("0").unwrap();
+        assert_eq!(
+            formatter.format_with_options(&picture, &42.into(), &arab_indic),
+            "٤٢"
+        );
+    }
+
+    #[test]
+    fn test_format_value() {
+        let picture = Picture::parse("#,##0").unwrap();
+        let mut formatter = Formatter::new();
+        assert_eq!(formatter.format_value(&picture, &1234u128), "1,234");
+        assert_eq!(formatter.format_value(&picture, &-1234i128), "-1,234");
+        assert_eq!(
+            formatter.format_value(&picture, &ibig::UBig::from(1_000_000u32)),
+            "1,000,000"
+        );
+    }
+}
@@ -0,0 +1,184 @@
+// Where grouping separators go among the digits of a number, as given by
+// the separators of a decimal digit pattern. The spec (fn:format-integer,
+// 4.6.1) calls grouping regular when the separators are all the same and
+// evenly spaced from the right; then they repeat indefinitely, beyond the
+// pattern. Otherwise they only go where the pattern has them. Integers and
+// the integer part of decimals are grouped the same way.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Grouping {
+    // a separator after every size digits, counted from the right
+    Regular { separator: char, size: usize },
+    // the separators, keyed by the amount of digits to their right, in
+    // ascending order
+    Irregular(Vec<(usize, char)>),
+}
+
+impl Grouping {
+    // the grouping for separators keyed by the amount of digits to their
+    // right, in ascending order, as in a pattern read from the right
+    pub(crate) fn new(separators: Vec<(usize, char)>) -> Self {
+        if let Some(&(size, separator)) = separators.first() {
+            let regular = size > 0
+                && separators
+                    .iter()
+                    .enumerate()
+                    .all(|(index, &(digits, c))| c == separator && digits == (index + 1) * size);
+            if regular {
+                return Grouping::Regular { separator, size };
+            }
+        }
+        Grouping::Irregular(separators)
+    }
+
+    // the grouping of a pattern given by its signs, where None is a digit
+    // and Some is a separator
+    pub(crate) fn from_signs(signs: impl DoubleEndedIterator<Item = Option<char>>) -> Self {
+        let mut separators = Vec::new();
+        let mut digits = 0;
+        for sign in signs.rev() {
+            match sign {
+                Some(separator) => separators.push((digits, separator)),
+                None => digits += 1,
+            }
+        }
+        Self::new(separators)
+    }
+
+    // the separator to put before the digit that has the given amount of
+    // digits to its right, if any. Beyond an irregular pattern there's an
+    // infinite supply of optional digits, so no more separators.
+    pub(crate) fn separator_at(&self, digits_to_the_right: usize) -> Option<char> {
+        match self {
+            Grouping::Regular { separator, size } => digits_to_the_right
+                .is_multiple_of(*size)
+                .then_some(*separator),
+            Grouping::Irregular(separators) => separators
+                .binary_search_by_key(&digits_to_the_right, |(digits, _)| *digits)
+                .ok()
+                .map(|index| separators[index].1),
+        }
+    }
+
+    // the amount of separators among the given amount of digits
+    pub(crate) fn separators_count(&self, total: usize) -> usize {
+        match self {
+            Grouping::Regular { size, .. } => {
+                if total == 0 || *size == 0 {
+                    return 0;
+                }
+                (total - 1) / *size
+            }
+            Grouping::Irregular(separators) => separators
+                .iter()
+                .take_while(|(digits, _)| *digits < total)
+                .count(),
+        }
+    }
+
+    // the length in bytes of the separators among the given amount of
+    // digits. This is a const fn so that pictures can size their output.
+    pub(crate) const fn separators_len(&self, total: usize) -> usize {
+        match self {
+            Grouping::Regular { separator, size } => {
+                if total == 0 || *size == 0 {
+                    return 0;
+                }
+                (total - 1) / *size * separator.len_utf8()
+            }
+            Grouping::Irregular(separators) => {
+                let separators = separators.as_slice();
+                let mut len = 0;
+                let mut index = 0;
+                while index < separators.len() && separators[index].0 < total {
+                    len += separators[index].1.len_utf8();
+                    index += 1;
+                }
+                len
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the grouping of a pattern like "#,##0", where digits are '0' and '#'
+    fn grouping(pattern: &str) -> Grouping {
+        Grouping::from_signs(
+            pattern
+                .chars()
+                .map(|c| (c != '0' && c != '#').then_some(c))
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
+    }
+
+    #[test]
+    fn test_regular() {
+        assert_eq!(
+            grouping("#,##0"),
+            Grouping::Regular {
+                separator: ',',
+                size: 3
+            }
+        );
+        assert_eq!(
+            grouping("0.000.000"),
+            Grouping::Regular {
+                separator: '.',
+                size: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_irregular() {
+        assert_eq!(
+            grouping("#,##,##0"),
+            Grouping::Irregular(vec![(3, ','), (5, ',')])
+        );
+        assert_eq!(
+            grouping("0.000,000"),
+            Grouping::Irregular(vec![(3, ','), (6, '.')])
+        );
+        assert_eq!(grouping("000"), Grouping::Irregular(vec![]));
+    }
+
+    #[test]
+    fn test_separator_at() {
+        let regular = grouping("#,##0");
+        assert_eq!(regular.separator_at(3), Some(','));
+        assert_eq!(regular.separator_at(4), None);
+        // regular grouping repeats beyond the pattern
+        assert_eq!(regular.separator_at(9), Some(','));
+        let irregular = grouping("#,##,##0");
+        assert_eq!(irregular.separator_at(3), Some(','));
+        assert_eq!(irregular.separator_at(5), Some(','));
+        assert_eq!(irregular.separator_at(7), None);
+    }
+
+    #[test]
+    fn test_separators_count() {
+        let regular = grouping("#,##0");
+        assert_eq!(regular.separators_count(3), 0);
+        assert_eq!(regular.separators_count(7), 2);
+        assert_eq!(regular.separators_count(usize::MAX), (usize::MAX - 1) / 3);
+        let irregular = grouping("#٬##,##0");
+        assert_eq!(irregular.separators_count(6), 2);
+        assert_eq!(irregular.separators_count(100), 2);
+    }
+
+    #[test]
+    fn test_separators_len() {
+        let regular = grouping("#,##0");
+        assert_eq!(regular.separators_len(3), 0);
+        assert_eq!(regular.separators_len(4), 1);
+        assert_eq!(regular.separators_len(7), 2);
+        let irregular = grouping("#٬##,##0");
+        assert_eq!(irregular.separators_len(5), 1);
+        assert_eq!(irregular.separators_len(6), 3);
+        assert_eq!(irregular.separators_len(100), 3);
+    }
+}
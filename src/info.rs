@@ -0,0 +1,138 @@
+// What a picture is made of, for stylesheet editors that check pictures
+// when a stylesheet is compiled and show what they'll format as, without
+// formatting anything.
+use crate::error::Error;
+use crate::format_integer::Picture;
+
+// the kind of primary format token of a picture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    // a decimal digit pattern, like "#,##0"
+    DecimalDigits,
+    // "w", "W" or "Ww"
+    Words,
+    // "i" or "I"
+    Roman,
+    // "a", "A", or the first letter of another alphabet
+    Alphabetic,
+    // letters that add up to the value, like "א"
+    Traditional,
+    // "①"
+    Circled,
+    // "፩"
+    Ethiopic,
+    // "一" or "壹"
+    Cjk,
+}
+
+// where the grouping separators of a decimal digit pattern go
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupingInfo {
+    // a separator after every size digits, counted from the right, however
+    // long the number
+    Regular { separator: char, size: usize },
+    // the separators, with the amount of digits to their right, in
+    // ascending order. There are no more beyond the pattern.
+    Irregular(Vec<(usize, char)>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PictureInfo {
+    pub(crate) kind: TokenKind,
+    pub(crate) grouping: Option<GroupingInfo>,
+    pub(crate) zero_digit: Option<char>,
+    pub(crate) ordinal: bool,
+}
+
+impl PictureInfo {
+    pub fn kind(&self) -> TokenKind {
+        self.kind
+    }
+
+    // the grouping of a decimal digit pattern, or None if it has no
+    // grouping separators or the picture isn't a pattern
+    pub fn grouping(&self) -> Option<&GroupingInfo> {
+        self.grouping.as_ref()
+    }
+
+    // the zero digit of the digit family a decimal digit pattern is written
+    // in, which is "0" if it only has optional digits, or None if the
+    // picture isn't a pattern
+    pub fn zero_digit(&self) -> Option<char> {
+        self.zero_digit
+    }
+
+    // whether the format modifier asks for ordinal numbers
+    pub fn ordinal(&self) -> bool {
+        self.ordinal
+    }
+}
+
+// check the picture, and describe it if it's valid. Errors are the same as
+// when formatting with it.
+pub fn validate_integer_picture(picture: &str) -> Result<PictureInfo, Error> {
+    Ok(Picture::parse(picture)?.info())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_digits() {
+        let info = validate_integer_picture("#,##0;o").unwrap();
+        assert_eq!(info.kind(), TokenKind::DecimalDigits);
+        assert_eq!(
+            info.grouping(),
+            Some(&GroupingInfo::Regular {
+                separator: ',',
+                size: 3
+            })
+        );
+        assert_eq!(info.zero_digit(), Some('0'));
+        assert!(info.ordinal());
+    }
+
+    #[test]
+    fn test_irregular_grouping() {
+        let info = validate_integer_picture("#,##,##0").unwrap();
+        assert_eq!(
+            info.grouping(),
+            Some(&GroupingInfo::Irregular(vec![(3, ','), (5, ',')]))
+        );
+    }
+
+    #[test]
+    fn test_digit_family() {
+        let info = validate_integer_picture("١٢٣").unwrap();
+        assert_eq!(info.zero_digit(), Some('٠'));
+        assert_eq!(info.grouping(), None);
+    }
+
+    #[test]
+    fn test_other_tokens() {
+        for (picture, kind) in [
+            ("w", TokenKind::Words),
+            ("Ww;o", TokenKind::Words),
+            ("I", TokenKind::Roman),
+            ("a", TokenKind::Alphabetic),
+            ("α", TokenKind::Alphabetic),
+            ("א", TokenKind::Traditional),
+            ("①", TokenKind::Circled),
+            ("፩", TokenKind::Ethiopic),
+            ("一", TokenKind::Cjk),
+        ] {
+            let info = validate_integer_picture(picture).unwrap();
+            assert_eq!(info.kind(), kind, "{}", picture);
+            assert_eq!(info.grouping(), None);
+            assert_eq!(info.zero_digit(), None);
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        let error = validate_integer_picture("0,").unwrap_err();
+        assert_eq!(error, Picture::parse("0,").unwrap_err());
+        assert_eq!(error.message(), "invalid picture string at 1");
+    }
+}
@@ -0,0 +1,191 @@
+use std::fmt::Write;
+
+use ibig::{IBig, UBig};
+
+// The integer types we can format. All formatting works on the ascii digits
+// of the absolute value, so that's what an integer has to produce; types
+// that can't be negative never have to deal with a sign.
+pub trait Integer {
+    // write the ascii digits of the absolute value to digits, and return
+    // whether the value is negative
+    fn write_digits(&self, digits: &mut String) -> bool;
+
+    // whether the value is negative and its absolute value, if that fits in
+    // a u128, so that its digits can be written without allocating. Types
+    // that don't have it always go through write_digits.
+    fn to_abs_u128(&self) -> Option<(bool, u128)> {
+        None
+    }
+}
+
+// the most digits a u128 has
+const MAX_U128_DIGITS: usize = 39;
+
+// call f with whether the value is negative and the ascii digits of its
+// absolute value. Values that fit in a u128 are written on the stack;
+// others are written to digits, which is cleared first.
+pub(crate) fn with_digits<I: Integer + ?Sized, R>(
+    i: &I,
+    digits: &mut String,
+    f: impl FnOnce(bool, &str) -> R,
+) -> R {
+    if let Some((is_negative, mut n)) = i.to_abs_u128() {
+        let mut buffer = [b'0'; MAX_U128_DIGITS];
+        // the digits come out from the right
+        let mut start = buffer.len() - 1;
+        buffer[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+        while n > 0 {
+            start -= 1;
+            buffer[start] = b'0' + (n % 10) as u8;
+            n /= 10;
+        }
+        // ascii digits are always valid UTF-8
+        if let Ok(s) = std::str::from_utf8(&buffer[start..]) {
+            return f(is_negative, s);
+        }
+    }
+    digits.clear();
+    let is_negative = i.write_digits(digits);
+    f(is_negative, digits)
+}
+
+impl Integer for IBig {
+    fn write_digits(&self, digits: &mut String) -> bool {
+        let start = digits.len();
+        // writing to a string can't fail
+        let _ = write!(digits, "{}", self);
+        // removing the sign is cheaper than taking the absolute value,
+        // which would copy the whole number
+        let is_negative = digits[start..].starts_with('-');
+        if is_negative {
+            digits.remove(start);
+        }
+        is_negative
+    }
+
+    fn to_abs_u128(&self) -> Option<(bool, u128)> {
+        let n = i128::try_from(self).ok()?;
+        Some((n < 0, n.unsigned_abs()))
+    }
+}
+
+impl Integer for UBig {
+    fn write_digits(&self, digits: &mut String) -> bool {
+        let _ = write!(digits, "{}", self);
+        false
+    }
+
+    fn to_abs_u128(&self) -> Option<(bool, u128)> {
+        Some((false, u128::try_from(self).ok()?))
+    }
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn write_digits(&self, digits: &mut String) -> bool {
+                    let _ = write!(digits, "{}", self);
+                    false
+                }
+
+                fn to_abs_u128(&self) -> Option<(bool, u128)> {
+                    // none of the types is wider than a u128
+                    Some((false, *self as u128))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn write_digits(&self, digits: &mut String) -> bool {
+                    // unsigned_abs doesn't overflow for the minimum value
+                    let _ = write!(digits, "{}", self.unsigned_abs());
+                    *self < 0
+                }
+
+                fn to_abs_u128(&self) -> Option<(bool, u128)> {
+                    Some((*self < 0, self.unsigned_abs() as u128))
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_signed!(i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(i: &(impl Integer + ?Sized)) -> (bool, String) {
+        let mut digits = String::new();
+        let is_negative = i.write_digits(&mut digits);
+        (is_negative, digits)
+    }
+
+    #[test]
+    fn test_ibig() {
+        assert_eq!(digits(&IBig::from(-123)), (true, "123".to_string()));
+        assert_eq!(digits(&IBig::from(0)), (false, "0".to_string()));
+    }
+
+    #[test]
+    fn test_ubig() {
+        let i: UBig = "123456789012345678901234567890".parse().unwrap();
+        assert_eq!(
+            digits(&i),
+            (false, "123456789012345678901234567890".to_string())
+        );
+    }
+
+    #[test]
+    fn test_primitive() {
+        assert_eq!(
+            digits(&u128::MAX),
+            (false, "340282366920938463463374607431768211455".to_string())
+        );
+        assert_eq!(
+            digits(&i128::MIN),
+            (true, "170141183460469231731687303715884105728".to_string())
+        );
+        assert_eq!(digits(&-5i8), (true, "5".to_string()));
+    }
+
+    // the digits as written by with_digits
+    fn stack_digits(i: &(impl Integer + ?Sized)) -> (bool, String) {
+        with_digits(i, &mut String::new(), |is_negative, digits| {
+            (is_negative, digits.to_string())
+        })
+    }
+
+    #[test]
+    fn test_with_digits() {
+        for i in [0i128, 7, -10, 1234567890, i128::MAX, i128::MIN] {
+            assert_eq!(stack_digits(&i), digits(&i));
+            assert_eq!(stack_digits(&IBig::from(i)), digits(&IBig::from(i)));
+        }
+        assert_eq!(stack_digits(&u128::MAX), digits(&u128::MAX));
+        assert_eq!(stack_digits(&0u8), (false, "0".to_string()));
+        // values beyond a u128 are written to the buffer
+        let i: IBig = "-1234567890123456789012345678901234567890".parse().unwrap();
+        let mut buffer = "left over".to_string();
+        with_digits(&i, &mut buffer, |is_negative, digits| {
+            assert!(is_negative);
+            assert_eq!(digits, "1234567890123456789012345678901234567890");
+        });
+    }
+
+    #[test]
+    fn test_appends() {
+        let mut digits = "12".to_string();
+        assert!(IBig::from(-3).write_digits(&mut digits));
+        assert_eq!(digits, "123");
+    }
+}
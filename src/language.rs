@@ -0,0 +1,159 @@
+use std::fmt;
+
+use icu::locid::{subtags::Region, LanguageIdentifier};
+use icu::locid_transform::fallback::LocaleFallbacker;
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Language(LanguageIdentifier);
+
+impl Language {
+    pub fn parse(tag: &str) -> Result<Self, Error> {
+        tag.parse::<LanguageIdentifier>()
+            .map(Language)
+            .map_err(|_| Error::InvalidLanguageTag(tag.to_string()))
+    }
+
+    pub(crate) fn new(identifier: LanguageIdentifier) -> Self {
+        Language(identifier)
+    }
+
+    pub fn identifier(&self) -> &LanguageIdentifier {
+        &self.0
+    }
+
+    pub fn region(&self) -> Option<Region> {
+        self.0.region
+    }
+
+    pub fn with_region(&self, region: Region) -> Self {
+        let mut identifier = self.0.clone();
+        identifier.region = Some(region);
+        Language(identifier)
+    }
+
+    // the languages to try in turn when this one isn't supported, from
+    // this one to the bare language, following the CLDR fallback chain:
+    // "pt-BR", then "pt"; "en-AU", then "en-001", then "en"
+    pub fn fallback_chain(&self) -> Vec<Language> {
+        let fallbacker = LocaleFallbacker::new().for_config(Default::default());
+        let mut iterator = fallbacker.fallback_for((&self.0).into());
+        let mut chain = Vec::new();
+        // the chain always ends with und, which isn't a language
+        while !iterator.get().is_und() {
+            chain.push(Language(iterator.get().get_langid()));
+            iterator.step();
+        }
+        chain
+    }
+
+    // the best match for this language in supported, following the CLDR
+    // fallback chain: "fr-CA", then "fr". None if nothing on the chain is
+    // supported.
+    pub fn best_match<'a>(&self, supported: &'a [Language]) -> Option<&'a Language> {
+        self.fallback_chain()
+            .into_iter()
+            .find_map(|candidate| supported.iter().find(|s| **s == candidate))
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn languages(tags: &[&str]) -> Vec<Language> {
+        tags.iter()
+            .map(|tag| Language::parse(tag).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Language::parse("en-us").unwrap().to_string(), "en-US");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            Language::parse("123456789"),
+            Err(Error::InvalidLanguageTag("123456789".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_region() {
+        assert_eq!(
+            Language::parse("de-ch").unwrap().region(),
+            Some("CH".parse().unwrap())
+        );
+        assert_eq!(Language::parse("de").unwrap().region(), None);
+    }
+
+    #[test]
+    fn test_with_region() {
+        let language = Language::parse("en-US").unwrap();
+        assert_eq!(
+            language.with_region("GB".parse().unwrap()).to_string(),
+            "en-GB"
+        );
+    }
+
+    #[test]
+    fn test_fallback_chain() {
+        let chain = |tag| {
+            Language::parse(tag)
+                .unwrap()
+                .fallback_chain()
+                .iter()
+                .map(Language::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(chain("pt-BR"), ["pt-BR", "pt"]);
+        assert_eq!(chain("en-AU"), ["en-AU", "en-001", "en"]);
+        assert_eq!(chain("de"), ["de"]);
+    }
+
+    #[test]
+    fn test_best_match_exact() {
+        let supported = languages(&["en", "fr-CA", "fr"]);
+        let language = Language::parse("fr-CA").unwrap();
+        assert_eq!(language.best_match(&supported), Some(&supported[1]));
+    }
+
+    #[test]
+    fn test_best_match_falls_back_to_language() {
+        let supported = languages(&["en", "fr"]);
+        let language = Language::parse("fr-CA").unwrap();
+        assert_eq!(language.best_match(&supported), Some(&supported[1]));
+    }
+
+    #[test]
+    fn test_best_match_drops_script() {
+        let supported = languages(&["en", "de"]);
+        let language = Language::parse("de-Latn-CH").unwrap();
+        assert_eq!(language.best_match(&supported), Some(&supported[1]));
+    }
+
+    #[test]
+    fn test_best_match_unsupported_region() {
+        let supported = languages(&["en-GB", "en", "de"]);
+        let language = Language::parse("de-CH").unwrap();
+        assert_eq!(language.best_match(&supported), Some(&supported[2]));
+        let language = Language::parse("en-AU").unwrap();
+        assert_eq!(language.best_match(&supported), Some(&supported[1]));
+    }
+
+    #[test]
+    fn test_best_match_none() {
+        let supported = languages(&["en", "fr"]);
+        let language = Language::parse("nl-BE").unwrap();
+        assert_eq!(language.best_match(&supported), None);
+    }
+}
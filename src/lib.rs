@@ -1,4 +1,54 @@
+// formatting must never panic, whatever the input, so we don't allow the
+// shortcuts that would
+#![cfg_attr(
+    not(test),
+    deny(clippy::unwrap_used, clippy::expect_used, clippy::panic)
+)]
+
+mod alphabetic;
+#[cfg(feature = "batch")]
+mod batch;
+pub mod casing;
+mod circled;
+mod cjk;
+mod context;
 mod digit;
+mod error;
+mod ethiopic;
+pub mod examples;
+mod fingerprint;
 mod format_integer;
+mod formatter;
+mod grouping;
+mod info;
+mod integer;
+mod language;
+mod lint;
+#[cfg(feature = "messages")]
+pub mod messages;
+mod options;
+mod roman;
+mod sink;
+mod tokens;
+mod traditional;
+pub mod translate;
+mod words;
+pub mod xpath;
 
-pub use format_integer::format_integer;
+#[cfg(feature = "batch")]
+pub use batch::{format_integer_column, format_integer_column_with_options, FormattedColumn};
+pub use context::FormatContext;
+pub use digit::transliterate_digits;
+pub use error::{Error, ErrorInfo, Feature, PictureError};
+pub use format_integer::{
+    format_integer, format_integer_lang, format_integer_value, format_integer_with_options, Picture,
+};
+pub use formatter::Formatter;
+pub use info::{validate_integer_picture, GroupingInfo, PictureInfo, TokenKind};
+pub use integer::Integer;
+pub use language::Language;
+pub use lint::{lint, Finding, FindingKind};
+pub use options::{FormatOptions, FormatOptionsBuilder};
+pub use roman::RomanOverflow;
+pub use sink::{ChunkKind, FormatSink};
+pub use words::NumberSpeller;
@@ -0,0 +1,220 @@
+// Findings about pictures that are valid, but probably not what was meant,
+// for stylesheet analyzers that warn authors about them. So far only
+// decimal digit patterns have findings.
+use crate::error::Error;
+use crate::format_integer::{is_decimal_digit_pattern, Picture};
+use crate::tokens::{CharClass, Symbols};
+
+// values practically always have at most this many digits, which is as
+// many as the largest i64 has
+const REALISTIC_DIGITS: usize = 19;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindingKind {
+    // optional digits that make no difference, like the first two of
+    // "###,##0"
+    RedundantOptionalDigits,
+    // groups of different sizes, like in "#,####,##0", where they were
+    // probably meant to be the same. Indian style grouping, with a larger
+    // group on the right ("#,##,##0"), is fine.
+    IrregularGrouping,
+    // different grouping separators in one pattern, like "#,###.##0"
+    MixedSeparators,
+    // grouping separators that only show for values with more digits than
+    // values realistically have
+    UnreachableGrouping,
+}
+
+// What's probably wrong with a picture, and where: the byte offset of the
+// character the finding is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Finding {
+    kind: FindingKind,
+    position: usize,
+}
+
+impl Finding {
+    pub fn kind(&self) -> FindingKind {
+        self.kind
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+// the findings for the picture, in the order of their positions. Invalid
+// pictures are reported as errors, as when they're parsed.
+pub fn lint(picture: &str) -> Result<Vec<Finding>, Error> {
+    Picture::parse(picture)?;
+    Ok(findings(picture))
+}
+
+// the findings for a picture that's known to be valid
+pub(crate) fn findings(picture: &str) -> Vec<Finding> {
+    // the format modifier follows the last semicolon, if there is one
+    let token = &picture[..picture.rfind(';').unwrap_or(picture.len())];
+    let chars: Vec<char> = token.chars().collect();
+    if !is_decimal_digit_pattern(&chars) {
+        return Vec::new();
+    }
+    let mut findings = Vec::new();
+    let signs: Vec<(usize, char)> = token.char_indices().collect();
+    let is_separator = |c: char| Symbols::INTEGER.classify(c) == CharClass::GroupSeparator;
+    let is_optional = |c: char| Symbols::INTEGER.classify(c) == CharClass::OptionalDigit;
+    let separators: Vec<(usize, char)> = signs
+        .iter()
+        .copied()
+        .filter(|(_, c)| is_separator(*c))
+        .collect();
+    // the amount of digits to the right of each separator, from the right
+    let distances: Vec<usize> = signs
+        .iter()
+        .rev()
+        .scan(0, |digits, (_, c)| {
+            if is_separator(*c) {
+                Some(Some(*digits))
+            } else {
+                *digits += 1;
+                Some(None)
+            }
+        })
+        .flatten()
+        .collect();
+
+    // the digits left of the leftmost separator, or all of them; one digit
+    // is needed before a separator
+    let leading = match separators.first() {
+        Some((position, _)) => &signs[..signs.partition_point(|(p, _)| p < position)],
+        None => &signs[..],
+    };
+    let needed = if separators.is_empty() || leading.iter().any(|(_, c)| !is_optional(*c)) {
+        0
+    } else {
+        1
+    };
+    let optional = leading.iter().filter(|(_, c)| is_optional(*c)).count();
+    if optional > needed {
+        if let Some((position, _)) = leading.iter().find(|(_, c)| is_optional(*c)) {
+            findings.push(Finding {
+                kind: FindingKind::RedundantOptionalDigits,
+                position: *position,
+            });
+        }
+    }
+
+    let first_separator = separators.last().map(|(_, c)| *c);
+    if let Some((position, _)) = separators
+        .iter()
+        .rev()
+        .find(|(_, c)| Some(*c) != first_separator)
+    {
+        findings.push(Finding {
+            kind: FindingKind::MixedSeparators,
+            position: *position,
+        });
+    } else {
+        let sizes: Vec<usize> = distances
+            .iter()
+            .scan(0, |previous, distance| {
+                let size = distance - *previous;
+                *previous = *distance;
+                Some(size)
+            })
+            .collect();
+        let irregular = (1..sizes.len())
+            .find(|&i| sizes[i] != sizes[i - 1] && !(i == 1 && sizes[1] < sizes[0]));
+        if let Some(i) = irregular {
+            findings.push(Finding {
+                kind: FindingKind::IrregularGrouping,
+                position: separators[separators.len() - 1 - i].0,
+            });
+        }
+    }
+
+    let mandatory = signs
+        .iter()
+        .filter(|(_, c)| matches!(Symbols::INTEGER.classify(*c), CharClass::MandatoryDigit(_)))
+        .count();
+    if let (Some(distance), Some((position, _))) = (distances.first(), separators.last()) {
+        // mandatory digits pad the value, so their separators always show
+        if *distance >= REALISTIC_DIGITS && *distance >= mandatory {
+            findings.push(Finding {
+                kind: FindingKind::UnreachableGrouping,
+                position: *position,
+            });
+        }
+    }
+
+    findings.sort_by_key(|finding| finding.position);
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(picture: &str) -> Vec<(FindingKind, usize)> {
+        lint(picture)
+            .unwrap()
+            .iter()
+            .map(|finding| (finding.kind(), finding.position()))
+            .collect()
+    }
+
+    #[test]
+    fn test_clean() {
+        for picture in [
+            "0",
+            "#,##0",
+            "0,000",
+            "#,##,##0",
+            "#,##0;o",
+            "i",
+            "w",
+            "١٬٢٣٤",
+        ] {
+            assert_eq!(kinds(picture), [], "{}", picture);
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(lint("0,").is_err());
+    }
+
+    #[test]
+    fn test_redundant_optional_digits() {
+        assert_eq!(kinds("##0"), [(FindingKind::RedundantOptionalDigits, 0)]);
+        assert_eq!(
+            kinds("###,##0"),
+            [(FindingKind::RedundantOptionalDigits, 0)]
+        );
+        assert_eq!(kinds("#0,000"), [(FindingKind::RedundantOptionalDigits, 0)]);
+    }
+
+    #[test]
+    fn test_irregular_grouping() {
+        assert_eq!(kinds("#,####,##0"), [(FindingKind::IrregularGrouping, 1)]);
+        assert_eq!(kinds("#,##,###,##0"), [(FindingKind::IrregularGrouping, 1)]);
+    }
+
+    #[test]
+    fn test_mixed_separators() {
+        assert_eq!(kinds("#,###.##0"), [(FindingKind::MixedSeparators, 1)]);
+        // positions are byte offsets
+        assert_eq!(kinds("٠٬٠٠٠.٠٠٠"), [(FindingKind::MixedSeparators, 2)]);
+    }
+
+    #[test]
+    fn test_unreachable_grouping() {
+        let picture = format!("#,{}", "#".repeat(18) + "0");
+        assert_eq!(kinds(&picture), [(FindingKind::UnreachableGrouping, 1)]);
+        // one digit less, and the largest values show it
+        let picture = format!("#,{}", "#".repeat(17) + "0");
+        assert_eq!(kinds(&picture), []);
+        // padding always shows it
+        let picture = format!("0,{}", "0".repeat(19));
+        assert_eq!(kinds(&picture), []);
+    }
+}
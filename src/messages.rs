@@ -0,0 +1,114 @@
+// Error messages in the language of whoever reads them, for hosts that show
+// errors to stylesheet authors. Messages are templates where each {} is
+// filled in with what the error carries, in order; the English ones are the
+// messages of the error registry. We ship German as well, and hosts can
+// bring their own translations as a MessageCatalog.
+use crate::error::{fill, Error};
+use crate::language::Language;
+
+// Translations of the message templates, looked up by the name of the
+// Error variant.
+pub trait MessageCatalog {
+    // the template for the variant, or None to fall back to English
+    fn template(&self, variant: &str) -> Option<&str>;
+}
+
+// a catalog of templates given as pairs of variant names and templates
+impl MessageCatalog for [(&str, &str)] {
+    fn template(&self, variant: &str) -> Option<&str> {
+        self.iter()
+            .find(|(name, _)| *name == variant)
+            .map(|(_, template)| *template)
+    }
+}
+
+const GERMAN: [(&str, &str); 10] = [
+    (
+        "InvalidPictureString",
+        "ungültiges Formatmuster an Position {}",
+    ),
+    (
+        "InvalidFormatModifier",
+        "ungültiger Formatmodifikator an Position {}",
+    ),
+    ("InvalidLanguageTag", "ungültiges Sprachkennzeichen {}"),
+    ("InvalidCountry", "ungültiges Land {}"),
+    ("InvalidDigitFamily", "{} ist keine Dezimalziffer"),
+    ("InvalidCurrencyCode", "ungültiger Währungscode {}"),
+    (
+        "ConflictingOptions",
+        "die Optionen {} und {} können nicht zusammen verwendet werden",
+    ),
+    (
+        "InvalidValidityBitmap",
+        "die Gültigkeitsbitmap passt nicht zu den Werten",
+    ),
+    (
+        "OutputTooLong",
+        "die Ausgabe überschreitet die zulässige Länge",
+    ),
+    ("Unsupported", "nicht unterstützte Nummerierungsfolge {}"),
+];
+
+// the message for the error in the language, or in English if we don't
+// have messages in it
+pub fn localized_message(error: &Error, language: &Language) -> String {
+    match language.identifier().language.as_str() {
+        "de" => catalog_message(error, GERMAN.as_slice()),
+        _ => error.message(),
+    }
+}
+
+// the message for the error from the catalog, or in English if the catalog
+// doesn't have it
+pub fn catalog_message(error: &Error, catalog: &(impl MessageCatalog + ?Sized)) -> String {
+    let template = catalog
+        .template(error.info().variant())
+        .unwrap_or(error.info().message());
+    fill(template, &error.message_arguments())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::PictureError;
+
+    #[test]
+    fn test_localized_message() {
+        let error = Error::InvalidPictureString(PictureError::new(3, Some(',')));
+        let german = Language::parse("de-CH").unwrap();
+        assert_eq!(
+            localized_message(&error, &german),
+            "ungültiges Formatmuster an Position 3"
+        );
+        let french = Language::parse("fr").unwrap();
+        assert_eq!(
+            localized_message(&error, &french),
+            "invalid picture string at 3"
+        );
+    }
+
+    #[test]
+    fn test_german_is_complete() {
+        for info in Error::registry() {
+            assert!(GERMAN.as_slice().template(info.variant()).is_some());
+        }
+    }
+
+    #[test]
+    fn test_catalog_message() {
+        let catalog = [("InvalidDigitFamily", "{} n'est pas un chiffre décimal")];
+        assert_eq!(
+            catalog_message(&Error::InvalidDigitFamily('x'), catalog.as_slice()),
+            "x n'est pas un chiffre décimal"
+        );
+        // missing templates fall back to English
+        assert_eq!(
+            catalog_message(
+                &Error::InvalidCurrencyCode("EU".to_string()),
+                catalog.as_slice()
+            ),
+            "invalid currency code EU"
+        );
+    }
+}
@@ -0,0 +1,603 @@
+use std::sync::Arc;
+
+use icu::locid::subtags::Region;
+
+use crate::digit::DigitFamily;
+use crate::error::Error;
+use crate::fingerprint::Fingerprinter;
+use crate::language::Language;
+use crate::roman::RomanOverflow;
+use crate::words::{NumberSpeller, Speller, Spelling};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FormatOptions {
+    language: Option<Language>,
+    country: Option<Region>,
+    strict: bool,
+    extensions: bool,
+    max_output_len: Option<usize>,
+    digit_family: Option<DigitFamily>,
+    currency_symbol: Option<String>,
+    currency_code: Option<String>,
+    accounting: bool,
+    nfc: bool,
+    roman_overflow: RomanOverflow,
+    roman_number_forms: bool,
+    number_spellers: Vec<(Language, Speller)>,
+}
+
+impl FormatOptions {
+    pub fn builder() -> FormatOptionsBuilder {
+        FormatOptionsBuilder::default()
+    }
+
+    pub fn language(&self) -> Option<&Language> {
+        self.language.as_ref()
+    }
+
+    pub fn country(&self) -> Option<Region> {
+        self.country
+    }
+
+    // whether to report features we don't support as errors, instead of
+    // falling back as the specification prescribes
+    pub fn strict(&self) -> bool {
+        self.strict
+    }
+
+    // whether pictures may use syntax beyond the specification, like sign
+    // placeholders
+    pub fn extensions(&self) -> bool {
+        self.extensions
+    }
+
+    // the maximum length of formatted output in bytes, if any
+    pub fn max_output_len(&self) -> Option<usize> {
+        self.max_output_len
+    }
+
+    // what a currency placeholder ("¤") in a picture stands for, with
+    // extensions
+    pub fn currency_symbol(&self) -> Option<&str> {
+        self.currency_symbol.as_deref()
+    }
+
+    // the ISO 4217 code a double currency placeholder ("¤¤") stands for,
+    // with extensions
+    pub fn currency_code(&self) -> Option<&str> {
+        self.currency_code.as_deref()
+    }
+
+    // whether negative values are put in parentheses, as in "(1,234)",
+    // instead of getting a minus sign
+    pub const fn accounting(&self) -> bool {
+        self.accounting
+    }
+
+    // whether formatted strings are guaranteed to be in Unicode
+    // normalization form C, for hosts that canonicalize their XML
+    pub fn nfc(&self) -> bool {
+        self.nfc
+    }
+
+    // how Roman numerals are written for values above 3999
+    pub const fn roman_overflow(&self) -> RomanOverflow {
+        self.roman_overflow
+    }
+
+    // whether Roman numerals are written in the characters of the Unicode
+    // Number Forms block, like "Ⅻ", instead of in letters
+    pub const fn roman_number_forms(&self) -> bool {
+        self.roman_number_forms
+    }
+
+    // the language numbers are spelled out in for the "w" format token:
+    // the first one on the fallback chain of the language that has a
+    // registered speller or our own words, or English. Our words that
+    // differ by region keep it, as "en-US" does, so "de-AT" resolves to
+    // "de" and "pt-BR" to "en", unless a speller is registered for "pt".
+    pub fn resolved_word_language(&self) -> Language {
+        Spelling::new(self.language(), self.number_spellers(), false).language(self.language())
+    }
+
+    // these options with another language, where the country still
+    // overrides the region
+    pub(crate) fn with_language(&self, language: Language) -> Self {
+        let language = match self.country {
+            Some(country) => language.with_region(country),
+            None => language,
+        };
+        Self {
+            language: Some(language),
+            ..self.clone()
+        }
+    }
+
+    // the digit family to write digits in, whatever family the picture uses
+    pub(crate) const fn digit_family(&self) -> Option<DigitFamily> {
+        self.digit_family
+    }
+
+    // the spellers registered for languages, in the order of registration
+    pub(crate) fn number_spellers(&self) -> &[(Language, Speller)] {
+        &self.number_spellers
+    }
+
+    // the options that change what a picture formats as, for its
+    // fingerprint. Options that only affect parsing or limit the output
+    // are left out. A speller can't be hashed, so we only hash the
+    // languages spellers are registered for.
+    pub(crate) fn fingerprint(&self, f: &mut Fingerprinter) {
+        if let Some(language) = &self.language {
+            f.write_u8(0);
+            write_str(f, &language.to_string());
+        }
+        if let Some(country) = self.country {
+            f.write_u8(1);
+            write_str(f, country.as_str());
+        }
+        if let Some(digit_family) = self.digit_family {
+            f.write_u8(2);
+            f.write_char(digit_family.zero());
+        }
+        if self.accounting {
+            f.write_u8(3);
+        }
+        if self.nfc {
+            f.write_u8(4);
+        }
+        match self.roman_overflow {
+            RomanOverflow::Decimal => {}
+            RomanOverflow::RepeatM(max) => {
+                f.write_u8(5);
+                f.write_usize(max.into());
+            }
+            RomanOverflow::Overline => f.write_u8(6),
+        }
+        if self.roman_number_forms {
+            f.write_u8(7);
+        }
+        for (language, _) in &self.number_spellers {
+            f.write_u8(8);
+            write_str(f, &language.to_string());
+        }
+    }
+}
+
+// a string, with its length so that it can't run into what follows
+fn write_str(f: &mut Fingerprinter, s: &str) {
+    f.write_usize(s.len());
+    f.write(s.as_bytes());
+}
+
+#[derive(Debug, Default)]
+pub struct FormatOptionsBuilder {
+    language: Option<String>,
+    country: Option<String>,
+    strict: bool,
+    extensions: bool,
+    max_output_len: Option<usize>,
+    digit: Option<char>,
+    currency_symbol: Option<String>,
+    currency_code: Option<String>,
+    accounting: bool,
+    nfc: bool,
+    roman_overflow: RomanOverflow,
+    roman_number_forms: bool,
+    number_spellers: Vec<(String, Speller)>,
+}
+
+impl FormatOptionsBuilder {
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    pub fn country(mut self, country: &str) -> Self {
+        self.country = Some(country.to_string());
+        self
+    }
+
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn extensions(mut self, extensions: bool) -> Self {
+        self.extensions = extensions;
+        self
+    }
+
+    pub fn max_output_len(mut self, max_output_len: usize) -> Self {
+        self.max_output_len = Some(max_output_len);
+        self
+    }
+
+    // write digits in the family of the given digit, so the picture only
+    // decides the grouping. Any digit of the family will do, like in a
+    // picture.
+    pub fn digit_family(mut self, digit: char) -> Self {
+        self.digit = Some(digit);
+        self
+    }
+
+    pub fn currency_symbol(mut self, symbol: &str) -> Self {
+        self.currency_symbol = Some(symbol.to_string());
+        self
+    }
+
+    pub fn currency_code(mut self, code: &str) -> Self {
+        self.currency_code = Some(code.to_string());
+        self
+    }
+
+    pub fn accounting(mut self, accounting: bool) -> Self {
+        self.accounting = accounting;
+        self
+    }
+
+    pub fn nfc(mut self, nfc: bool) -> Self {
+        self.nfc = nfc;
+        self
+    }
+
+    pub fn roman_overflow(mut self, overflow: RomanOverflow) -> Self {
+        self.roman_overflow = overflow;
+        self
+    }
+
+    pub fn roman_number_forms(mut self, number_forms: bool) -> Self {
+        self.roman_number_forms = number_forms;
+        self
+    }
+
+    // spell out numbers in the language with the speller, for languages we
+    // don't have words for, or to replace ours. The speller is used for
+    // any language whose fallback chain has the language, so one
+    // registered for "cy" is also used for "cy-GB". When a language is
+    // registered more than once, the first speller wins.
+    pub fn number_speller(mut self, language: &str, speller: impl NumberSpeller + 'static) -> Self {
+        self.number_spellers
+            .push((language.to_string(), Speller(Arc::new(speller))));
+        self
+    }
+
+    pub fn build(self) -> Result<FormatOptions, Error> {
+        // we only validate when building, so that the builder methods can
+        // be chained without intermediate results
+        if self.strict && self.extensions {
+            // strict mode is about sticking to the specification
+            return Err(Error::ConflictingOptions("strict", "extensions"));
+        }
+        let language = self
+            .language
+            .map(|language| Language::parse(&language))
+            .transpose()?;
+        let country = self
+            .country
+            .map(|country| {
+                country
+                    .parse::<Region>()
+                    .map_err(|_| Error::InvalidCountry(country))
+            })
+            .transpose()?;
+        // an explicit country overrides any region in the language tag, so
+        // that language matching takes it into account
+        let language = match (language, country) {
+            (Some(language), Some(country)) => Some(language.with_region(country)),
+            (language, _) => language,
+        };
+        let digit_family = self
+            .digit
+            .map(|digit| DigitFamily::new(digit).ok_or(Error::InvalidDigitFamily(digit)))
+            .transpose()?;
+        // currency codes are three letters, which we normalize to upper
+        // case like countries
+        let currency_code = self
+            .currency_code
+            .map(|code| {
+                if code.len() == 3 && code.bytes().all(|b| b.is_ascii_alphabetic()) {
+                    Ok(code.to_ascii_uppercase())
+                } else {
+                    Err(Error::InvalidCurrencyCode(code))
+                }
+            })
+            .transpose()?;
+        let number_spellers = self
+            .number_spellers
+            .into_iter()
+            .map(|(language, speller)| Ok((Language::parse(&language)?, speller)))
+            .collect::<Result<_, Error>>()?;
+        Ok(FormatOptions {
+            language,
+            country,
+            strict: self.strict,
+            extensions: self.extensions,
+            max_output_len: self.max_output_len,
+            digit_family,
+            currency_symbol: self.currency_symbol,
+            currency_code,
+            accounting: self.accounting,
+            nfc: self.nfc,
+            roman_overflow: self.roman_overflow,
+            roman_number_forms: self.roman_number_forms,
+            number_spellers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_options() {
+        let options = FormatOptions::builder().build().unwrap();
+        assert_eq!(options, FormatOptions::default());
+        assert_eq!(options.language(), None);
+        assert!(!options.strict());
+    }
+
+    #[test]
+    fn test_language() {
+        let options = FormatOptions::builder().language("fr").build().unwrap();
+        assert_eq!(options.language().unwrap().to_string(), "fr");
+    }
+
+    #[test]
+    fn test_language_is_normalized() {
+        let options = FormatOptions::builder().language("FR_ca").build().unwrap();
+        assert_eq!(options.language().unwrap().to_string(), "fr-CA");
+    }
+
+    #[test]
+    fn test_invalid_language() {
+        assert_eq!(
+            FormatOptions::builder().language("not a language").build(),
+            Err(Error::InvalidLanguageTag("not a language".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_country_is_normalized() {
+        let options = FormatOptions::builder().country("gb").build().unwrap();
+        assert_eq!(options.country(), Some("GB".parse().unwrap()));
+        assert_eq!(options.language(), None);
+    }
+
+    #[test]
+    fn test_country_overrides_language_region() {
+        let options = FormatOptions::builder()
+            .language("de-DE")
+            .country("ch")
+            .build()
+            .unwrap();
+        assert_eq!(options.language().unwrap().to_string(), "de-CH");
+    }
+
+    #[test]
+    fn test_invalid_country() {
+        assert_eq!(
+            FormatOptions::builder().country("england").build(),
+            Err(Error::InvalidCountry("england".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_strict() {
+        let options = FormatOptions::builder().strict(true).build().unwrap();
+        assert!(options.strict());
+    }
+
+    #[test]
+    fn test_max_output_len() {
+        let options = FormatOptions::builder().max_output_len(10).build().unwrap();
+        assert_eq!(options.max_output_len(), Some(10));
+        assert_eq!(FormatOptions::default().max_output_len(), None);
+    }
+
+    #[test]
+    fn test_digit_family() {
+        let options = FormatOptions::builder().digit_family('٣').build().unwrap();
+        assert_eq!(options.digit_family().unwrap().zero(), '٠');
+    }
+
+    struct Digits;
+
+    impl NumberSpeller for Digits {
+        fn write_cardinal(&self, digits: &str, output: &mut String) {
+            output.push_str(digits);
+        }
+
+        fn negative_word(&self) -> &str {
+            "-"
+        }
+    }
+
+    #[test]
+    fn test_number_speller() {
+        let options = FormatOptions::builder()
+            .number_speller("cy", Digits)
+            .build()
+            .unwrap();
+        assert_eq!(options.number_spellers().len(), 1);
+        assert_eq!(options.number_spellers()[0].0.to_string(), "cy");
+        // options share their spellers when cloned
+        assert_eq!(options.clone(), options);
+        let other = FormatOptions::builder()
+            .number_speller("cy", Digits)
+            .build()
+            .unwrap();
+        assert_ne!(options, other);
+    }
+
+    #[test]
+    fn test_invalid_number_speller_language() {
+        assert_eq!(
+            FormatOptions::builder()
+                .number_speller("not a language", Digits)
+                .build(),
+            Err(Error::InvalidLanguageTag("not a language".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_invalid_digit_family() {
+        assert_eq!(
+            FormatOptions::builder().digit_family('a').build(),
+            Err(Error::InvalidDigitFamily('a'))
+        );
+    }
+
+    #[test]
+    fn test_with_language() {
+        let options = FormatOptions::builder()
+            .language("en")
+            .country("at")
+            .build()
+            .unwrap();
+        let options = options.with_language(Language::parse("de").unwrap());
+        assert_eq!(options.language().unwrap().to_string(), "de-AT");
+    }
+
+    #[test]
+    fn test_extensions() {
+        let options = FormatOptions::builder().extensions(true).build().unwrap();
+        assert!(options.extensions());
+        assert!(!FormatOptions::default().extensions());
+    }
+
+    #[test]
+    fn test_strict_conflicts_with_extensions() {
+        assert_eq!(
+            FormatOptions::builder()
+                .strict(true)
+                .extensions(true)
+                .build(),
+            Err(Error::ConflictingOptions("strict", "extensions"))
+        );
+    }
+
+    #[test]
+    fn test_currency() {
+        let options = FormatOptions::builder()
+            .currency_symbol("€")
+            .currency_code("eur")
+            .build()
+            .unwrap();
+        assert_eq!(options.currency_symbol(), Some("€"));
+        assert_eq!(options.currency_code(), Some("EUR"));
+        assert_eq!(FormatOptions::default().currency_code(), None);
+    }
+
+    #[test]
+    fn test_invalid_currency_code() {
+        assert_eq!(
+            FormatOptions::builder().currency_code("euro").build(),
+            Err(Error::InvalidCurrencyCode("euro".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_accounting() {
+        let options = FormatOptions::builder().accounting(true).build().unwrap();
+        assert!(options.accounting());
+        assert!(!FormatOptions::default().accounting());
+    }
+
+    #[test]
+    fn test_nfc() {
+        let options = FormatOptions::builder().nfc(true).build().unwrap();
+        assert!(options.nfc());
+        assert!(!FormatOptions::default().nfc());
+    }
+
+    #[test]
+    fn test_roman_overflow() {
+        let options = FormatOptions::builder()
+            .roman_overflow(RomanOverflow::Overline)
+            .build()
+            .unwrap();
+        assert_eq!(options.roman_overflow(), RomanOverflow::Overline);
+        assert_eq!(
+            FormatOptions::default().roman_overflow(),
+            RomanOverflow::Decimal
+        );
+    }
+
+    #[test]
+    fn test_roman_number_forms() {
+        let options = FormatOptions::builder()
+            .roman_number_forms(true)
+            .build()
+            .unwrap();
+        assert!(options.roman_number_forms());
+        assert!(!FormatOptions::default().roman_number_forms());
+    }
+
+    // counts in Portuguese, as far as the tests need
+    struct Portuguese;
+
+    impl NumberSpeller for Portuguese {
+        fn write_cardinal(&self, _digits: &str, output: &mut String) {
+            output.push_str("três");
+        }
+
+        fn negative_word(&self) -> &str {
+            "menos "
+        }
+    }
+
+    #[test]
+    fn test_resolved_word_language() {
+        let resolved = |tag| {
+            FormatOptions::builder()
+                .language(tag)
+                .build()
+                .unwrap()
+                .resolved_word_language()
+                .to_string()
+        };
+        assert_eq!(resolved("de-AT"), "de");
+        assert_eq!(resolved("de-Latn-DE"), "de");
+        assert_eq!(resolved("de-CH"), "de-CH");
+        assert_eq!(resolved("de-LI"), "de-LI");
+        assert_eq!(resolved("en-AU"), "en");
+        assert_eq!(resolved("en-US"), "en-US");
+        assert_eq!(resolved("ko-KR"), "ko");
+        assert_eq!(resolved("pt-BR"), "en");
+        assert_eq!(
+            FormatOptions::default()
+                .resolved_word_language()
+                .to_string(),
+            "en"
+        );
+        let options = FormatOptions::builder()
+            .language("de")
+            .country("us")
+            .build()
+            .unwrap();
+        assert_eq!(options.resolved_word_language().to_string(), "de");
+    }
+
+    #[test]
+    fn test_resolved_word_language_speller() {
+        let options = FormatOptions::builder()
+            .language("pt-BR")
+            .number_speller("pt", Portuguese)
+            .build()
+            .unwrap();
+        assert_eq!(options.resolved_word_language().to_string(), "pt");
+        assert_eq!(
+            crate::format_integer::format_integer_with_options(3.into(), "w", &options).unwrap(),
+            "três"
+        );
+        // the speller doesn't affect other languages
+        let options = FormatOptions::builder()
+            .language("en-US")
+            .number_speller("pt", Portuguese)
+            .build()
+            .unwrap();
+        assert_eq!(options.resolved_word_language().to_string(), "en-US");
+    }
+}
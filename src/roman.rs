@@ -0,0 +1,297 @@
+// Roman numerals, for the "i" and "I" format tokens. They're only defined
+// from 1 to 3999; other values are formatted as decimal numbers instead, as
+// the specification prescribes for numbers a numbering sequence can't
+// express, unless the options ask for one of the ways larger numerals were
+// written. We write them in lower case; upper case is applied afterwards.
+
+// the largest value with a standard Roman numeral
+pub(crate) const MAX: u16 = 3999;
+
+// What to do with values above 3999, which have no standard Roman numeral.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RomanOverflow {
+    // write them in decimal, as the specification prescribes
+    #[default]
+    Decimal,
+    // repeat the m for every thousand, as in "mmmmcdxx" for 4420, up to
+    // the given value; larger values are still written in decimal
+    RepeatM(u16),
+    // write the thousands with a bar over them, as in "i̅v̅cdxx" for 4420,
+    // up to 3999999
+    Overline,
+}
+
+impl RomanOverflow {
+    // the largest value we write as a Roman numeral
+    fn max(self) -> u32 {
+        match self {
+            RomanOverflow::Decimal => MAX.into(),
+            RomanOverflow::RepeatM(max) => max.max(MAX).into(),
+            RomanOverflow::Overline => u32::from(MAX) * 1000 + 999,
+        }
+    }
+}
+
+// multiplies the letter before it by a thousand
+const OVERLINE: char = '\u{305}';
+
+// the numerals from one to twelve in the Number Forms block, from "ⅰ" to
+// "ⅻ"; they're consecutive code points
+const NUMBER_FORM_ONE: u32 = 0x2170;
+const NUMBER_FORM_MAX: u32 = 12;
+
+// the letters of the Number Forms block
+fn number_form(letter: char) -> char {
+    match letter {
+        'i' => 'ⅰ',
+        'v' => 'ⅴ',
+        'x' => 'ⅹ',
+        'l' => 'ⅼ',
+        'c' => 'ⅽ',
+        'd' => 'ⅾ',
+        'm' => 'ⅿ',
+        c => c,
+    }
+}
+
+// the maximum length in bytes of a Roman numeral, for "mmmdccclxxxviii"
+pub(crate) const MAX_LEN: usize = 15;
+
+// the longest numeral below a thousand, "dccclxxxviii"
+const MAX_LEN_BELOW_THOUSAND: usize = 12;
+
+// the maximum amount of letters of a standard numeral for a value with the
+// given amount of digits, as in "viii" and "lxxxviii"
+const fn max_letters(digit_count: usize) -> usize {
+    match digit_count {
+        0 | 1 => 4,
+        2 => 8,
+        3 => MAX_LEN_BELOW_THOUSAND,
+        _ => MAX_LEN,
+    }
+}
+
+// the maximum length in bytes of the numeral for a value with the given
+// amount of digits, as written with the overflow policy, and in the
+// characters of the Number Forms block if asked for
+pub(crate) const fn max_len(
+    digit_count: usize,
+    overflow: RomanOverflow,
+    number_forms: bool,
+) -> usize {
+    // the number forms are all three bytes long
+    let letter_len = if number_forms { 3 } else { 1 };
+    if digit_count <= 3 {
+        return max_letters(digit_count) * letter_len;
+    }
+    let len = match overflow {
+        RomanOverflow::Decimal => MAX_LEN * letter_len,
+        // an m for every thousand up to the maximum, and the rest
+        RomanOverflow::RepeatM(max) => {
+            let thousands = max as usize / 1000;
+            let thousands = if digit_count == 4 && thousands > 9 {
+                9
+            } else {
+                thousands
+            };
+            (thousands + MAX_LEN_BELOW_THOUSAND) * letter_len
+        }
+        // the thousands as a numeral with a bar over every letter, and the
+        // rest
+        RomanOverflow::Overline => {
+            max_letters(digit_count - 3) * (letter_len + OVERLINE.len_utf8())
+                + MAX_LEN_BELOW_THOUSAND * letter_len
+        }
+    };
+    if len > MAX_LEN * letter_len {
+        len
+    } else {
+        MAX_LEN * letter_len
+    }
+}
+
+const NUMERALS: [(u32, &str); 13] = [
+    (1000, "m"),
+    (900, "cm"),
+    (500, "d"),
+    (400, "cd"),
+    (100, "c"),
+    (90, "xc"),
+    (50, "l"),
+    (40, "xl"),
+    (10, "x"),
+    (9, "ix"),
+    (5, "v"),
+    (4, "iv"),
+    (1, "i"),
+];
+
+// the value of the ascii digits, if it can be written as a Roman numeral
+pub(crate) fn value(digits: &str, overflow: RomanOverflow) -> Option<u32> {
+    let digits = digits.trim_start_matches('0');
+    if digits.len() > 7 {
+        return None;
+    }
+    let value = digits
+        .bytes()
+        .fold(0, |value, digit| value * 10 + u32::from(digit - b'0'));
+    (1..=overflow.max()).contains(&value).then_some(value)
+}
+
+// append the Roman numeral for a value returned by value, in lower case,
+// in the characters of the Number Forms block ("ⅹⅳ") if asked for, which
+// render better in CJK typesetting. Those have a single character for each
+// numeral up to twelve ("ⅻ").
+pub(crate) fn write(value: u32, overflow: RomanOverflow, number_forms: bool, output: &mut String) {
+    if !number_forms {
+        write_letters(value, overflow, output);
+    } else if value <= NUMBER_FORM_MAX {
+        output.extend(char::from_u32(NUMBER_FORM_ONE + value - 1));
+    } else {
+        let start = output.len();
+        write_letters(value, overflow, output);
+        let letters: String = output.drain(start..).collect();
+        output.extend(letters.chars().map(number_form));
+    }
+}
+
+fn write_letters(value: u32, overflow: RomanOverflow, output: &mut String) {
+    if overflow == RomanOverflow::Overline && value > u32::from(MAX) {
+        let start = output.len();
+        write_numerals(value / 1000, output);
+        let thousands: String = output.drain(start..).collect();
+        for letter in thousands.chars() {
+            output.push(letter);
+            output.push(OVERLINE);
+        }
+        write_numerals(value % 1000, output);
+    } else {
+        write_numerals(value, output);
+    }
+}
+
+fn write_numerals(mut value: u32, output: &mut String) {
+    for (amount, numeral) in NUMERALS {
+        while value >= amount {
+            output.push_str(numeral);
+            value -= amount;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roman(value: u32) -> String {
+        roman_with(value, RomanOverflow::Decimal)
+    }
+
+    fn roman_with(value: u32, overflow: RomanOverflow) -> String {
+        let mut output = String::new();
+        write(value, overflow, false, &mut output);
+        output
+    }
+
+    fn number_forms(value: u32) -> String {
+        let mut output = String::new();
+        write(value, RomanOverflow::Overline, true, &mut output);
+        output
+    }
+
+    #[test]
+    fn test_write() {
+        assert_eq!(roman(1), "i");
+        assert_eq!(roman(4), "iv");
+        assert_eq!(roman(9), "ix");
+        assert_eq!(roman(14), "xiv");
+        assert_eq!(roman(1999), "mcmxcix");
+        assert_eq!(roman(2024), "mmxxiv");
+        assert_eq!(roman(3888), "mmmdccclxxxviii");
+    }
+
+    #[test]
+    fn test_value() {
+        let value = |digits| value(digits, RomanOverflow::Decimal);
+        assert_eq!(value("1"), Some(1));
+        assert_eq!(value("3999"), Some(3999));
+        assert_eq!(value("0003999"), Some(3999));
+        assert_eq!(value("0"), None);
+        assert_eq!(value("4000"), None);
+        assert_eq!(value("123456789"), None);
+    }
+
+    #[test]
+    fn test_value_overflow() {
+        assert_eq!(value("5000", RomanOverflow::RepeatM(9999)), Some(5000));
+        assert_eq!(value("10000", RomanOverflow::RepeatM(9999)), None);
+        // the maximum is never below the standard one
+        assert_eq!(value("3999", RomanOverflow::RepeatM(10)), Some(3999));
+        assert_eq!(value("3999999", RomanOverflow::Overline), Some(3999999));
+        assert_eq!(value("4000000", RomanOverflow::Overline), None);
+    }
+
+    #[test]
+    fn test_repeat_m() {
+        assert_eq!(roman_with(4420, RomanOverflow::RepeatM(9999)), "mmmmcdxx");
+        assert_eq!(roman_with(6000, RomanOverflow::RepeatM(9999)), "mmmmmm");
+    }
+
+    #[test]
+    fn test_overline() {
+        assert_eq!(
+            roman_with(4420, RomanOverflow::Overline),
+            "i\u{305}v\u{305}cdxx"
+        );
+        assert_eq!(roman_with(1_000_000, RomanOverflow::Overline), "m\u{305}");
+        // values with a standard numeral keep it
+        assert_eq!(roman_with(3999, RomanOverflow::Overline), "mmmcmxcix");
+    }
+
+    #[test]
+    fn test_number_forms() {
+        assert_eq!(number_forms(1), "ⅰ");
+        assert_eq!(number_forms(4), "ⅳ");
+        assert_eq!(number_forms(12), "ⅻ");
+        assert_eq!(number_forms(14), "ⅹⅰⅴ");
+        assert_eq!(number_forms(2024), "ⅿⅿⅹⅹⅰⅴ");
+        assert_eq!(number_forms(4001), "ⅰ\u{305}ⅴ\u{305}ⅰ");
+    }
+
+    #[test]
+    fn test_max_len() {
+        assert!((1..=MAX.into()).all(|value| roman(value).len() <= MAX_LEN));
+        for value in [8, 88, 888, 3888] {
+            let digit_count = value.to_string().len();
+            assert_eq!(
+                roman(value).len(),
+                max_len(digit_count, RomanOverflow::Decimal, false)
+            );
+        }
+    }
+
+    #[test]
+    fn test_max_len_overflow() {
+        for (value, overflow) in [
+            (9888, RomanOverflow::RepeatM(9999)),
+            (9888, RomanOverflow::RepeatM(u16::MAX)),
+            (4888, RomanOverflow::Overline),
+            (65535, RomanOverflow::RepeatM(u16::MAX)),
+            (88888, RomanOverflow::Overline),
+            (3_888_888, RomanOverflow::Overline),
+        ] {
+            let digit_count = value.to_string().len();
+            let len = roman_with(value, overflow).len();
+            assert!(len <= max_len(digit_count, overflow, false), "{}", value);
+            let mut output = String::new();
+            write(value, overflow, true, &mut output);
+            assert!(
+                output.len() <= max_len(digit_count, overflow, true),
+                "{}",
+                value
+            );
+        }
+        assert_eq!(max_len(7, RomanOverflow::Overline, false), 57);
+        assert_eq!(max_len(4, RomanOverflow::Decimal, true), 45);
+    }
+}
@@ -0,0 +1,65 @@
+use std::fmt;
+
+// The parts of formatted output, as handed to a FormatSink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkKind {
+    Sign,
+    Digits,
+    Separator,
+    // a number spelled out in words
+    Words,
+    // what follows the digits, like the "st" of "1st"
+    Suffix,
+    // a currency symbol or code
+    Currency,
+}
+
+// Receives formatted output in chunks, left to right, so that a host (such
+// as a streaming serializer) can forward them without building an
+// intermediate string.
+pub trait FormatSink {
+    fn write_chunk(&mut self, kind: ChunkKind, chunk: &str);
+}
+
+impl FormatSink for String {
+    fn write_chunk(&mut self, _kind: ChunkKind, chunk: &str) {
+        self.push_str(chunk);
+    }
+}
+
+// Hands the chunks to a fmt::Write, such as a Formatter or a String owned
+// by the caller, keeping the first error it reports; the chunks after it
+// are dropped.
+pub(crate) struct WriteSink<'a, W: fmt::Write + ?Sized> {
+    out: &'a mut W,
+    result: fmt::Result,
+}
+
+impl<'a, W: fmt::Write + ?Sized> WriteSink<'a, W> {
+    pub(crate) fn new(out: &'a mut W) -> Self {
+        Self {
+            out,
+            result: Ok(()),
+        }
+    }
+
+    pub(crate) fn finish(self) -> fmt::Result {
+        self.result
+    }
+}
+
+impl<W: fmt::Write + ?Sized> FormatSink for WriteSink<'_, W> {
+    fn write_chunk(&mut self, _kind: ChunkKind, chunk: &str) {
+        if self.result.is_ok() {
+            self.result = self.out.write_str(chunk);
+        }
+    }
+}
+
+// collect the chunks themselves, which is mostly useful for tests and
+// debugging
+impl FormatSink for Vec<(ChunkKind, String)> {
+    fn write_chunk(&mut self, kind: ChunkKind, chunk: &str) {
+        self.push((kind, chunk.to_string()));
+    }
+}
@@ -0,0 +1,94 @@
+// What the characters of a decimal digit pattern stand for. Which
+// characters are the optional digit and the grouping separator depends on
+// the picture language: fn:format-integer has fixed ones, while
+// fn:format-number takes them from a decimal format, so the
+// classification is parameterized by a table of symbols.
+use crate::digit::{is_group_separator, DigitFamily};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CharClass {
+    OptionalDigit,
+    // a digit of the family
+    MandatoryDigit(DigitFamily),
+    GroupSeparator,
+    // a character that isn't part of a digit pattern, like a letter. These
+    // are passive characters in fn:format-number, and make a format-integer
+    // pattern invalid.
+    Passive,
+}
+
+impl CharClass {
+    pub(crate) fn is_digit(self) -> bool {
+        matches!(
+            self,
+            CharClass::OptionalDigit | CharClass::MandatoryDigit(_)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Symbols {
+    optional_digit: char,
+    // the grouping separator, or None if any character that isn't a letter
+    // or a number is one
+    grouping_separator: Option<char>,
+}
+
+impl Symbols {
+    // the symbols of fn:format-integer pictures
+    pub(crate) const INTEGER: Symbols = Symbols {
+        optional_digit: '#',
+        grouping_separator: None,
+    };
+
+    pub(crate) fn classify(&self, c: char) -> CharClass {
+        if c == self.optional_digit {
+            return CharClass::OptionalDigit;
+        }
+        if let Some(family) = DigitFamily::new(c) {
+            return CharClass::MandatoryDigit(family);
+        }
+        let is_separator = match self.grouping_separator {
+            Some(separator) => c == separator,
+            None => is_group_separator(c),
+        };
+        if is_separator {
+            CharClass::GroupSeparator
+        } else {
+            CharClass::Passive
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer() {
+        let symbols = Symbols::INTEGER;
+        assert_eq!(symbols.classify('#'), CharClass::OptionalDigit);
+        assert_eq!(
+            symbols.classify('٣'),
+            CharClass::MandatoryDigit(DigitFamily::new('٠').unwrap())
+        );
+        assert_eq!(symbols.classify(','), CharClass::GroupSeparator);
+        assert_eq!(symbols.classify(' '), CharClass::GroupSeparator);
+        assert_eq!(symbols.classify('a'), CharClass::Passive);
+    }
+
+    #[test]
+    fn test_custom_symbols() {
+        // as in a decimal format with a grouping separator of "." and the
+        // digit sign "x"
+        let symbols = Symbols {
+            optional_digit: 'x',
+            grouping_separator: Some('.'),
+        };
+        assert_eq!(symbols.classify('x'), CharClass::OptionalDigit);
+        assert_eq!(symbols.classify('.'), CharClass::GroupSeparator);
+        assert_eq!(symbols.classify('#'), CharClass::Passive);
+        assert_eq!(symbols.classify(','), CharClass::Passive);
+        assert!(symbols.classify('7').is_digit());
+    }
+}
@@ -0,0 +1,117 @@
+// Traditional numerals, where letters of an alphabet stand for values and
+// a numeral is written as the letters that add up to its value, like "קכג"
+// (100 + 20 + 3) in Hebrew. They only go up to some maximum; other values
+// are formatted as decimal numbers instead, as for Roman numerals.
+use crate::fingerprint::Fingerprinter;
+
+mod armenian;
+mod georgian;
+mod hebrew;
+mod tamil;
+
+// What every traditional numbering system implements. To add one, give it
+// a module implementing this, and a variant of TraditionalSystem for its
+// format token.
+pub(crate) trait TraditionalNumerals {
+    // the value of the ascii digits, if it can be written in these numerals
+    fn value(&self, digits: &str) -> Option<u16>;
+
+    // append the numeral for a value returned by value
+    fn write(&self, value: u16, output: &mut String);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TraditionalSystem {
+    Hebrew,
+    Armenian,
+    Georgian,
+    Tamil,
+}
+
+impl TraditionalSystem {
+    // the system whose format token is given. Tamil numerals are only
+    // used with the "t" format modifier, so they're not found here.
+    pub(crate) fn new(token: char) -> Option<Self> {
+        match token {
+            'א' => Some(TraditionalSystem::Hebrew),
+            'Ա' => Some(TraditionalSystem::Armenian),
+            'ა' => Some(TraditionalSystem::Georgian),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn numerals(self) -> &'static dyn TraditionalNumerals {
+        match self {
+            TraditionalSystem::Hebrew => &hebrew::Hebrew,
+            TraditionalSystem::Armenian => &armenian::Armenian,
+            TraditionalSystem::Georgian => &georgian::Georgian,
+            TraditionalSystem::Tamil => &tamil::Tamil,
+        }
+    }
+
+    // the maximum length in bytes of a numeral; this is a const fn so that
+    // pictures can size their output
+    pub(crate) const fn max_len(self) -> usize {
+        match self {
+            TraditionalSystem::Hebrew => hebrew::MAX_LEN,
+            TraditionalSystem::Armenian => armenian::MAX_LEN,
+            TraditionalSystem::Georgian => georgian::MAX_LEN,
+            TraditionalSystem::Tamil => tamil::MAX_LEN,
+        }
+    }
+
+    pub(crate) fn fingerprint(&self, f: &mut Fingerprinter) {
+        f.write_u8(match self {
+            TraditionalSystem::Hebrew => 0,
+            TraditionalSystem::Armenian => 1,
+            TraditionalSystem::Georgian => 2,
+            TraditionalSystem::Tamil => 3,
+        });
+    }
+}
+
+// the value of at most five ascii digits, as long as it's from 1 to max
+fn small_value(digits: &str, max: u16) -> Option<u16> {
+    let digits = digits.trim_start_matches('0');
+    if digits.len() > 5 {
+        return None;
+    }
+    let value = digits
+        .bytes()
+        .fold(0, |value, digit| value * 10 + u32::from(digit - b'0'));
+    u16::try_from(value)
+        .ok()
+        .filter(|value| (1..=max).contains(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_len() {
+        for system in [
+            TraditionalSystem::Hebrew,
+            TraditionalSystem::Armenian,
+            TraditionalSystem::Georgian,
+            TraditionalSystem::Tamil,
+        ] {
+            let numerals = system.numerals();
+            for value in 1..=20000 {
+                let mut output = String::new();
+                if let Some(value) = numerals.value(&value.to_string()) {
+                    numerals.write(value, &mut output);
+                }
+                assert!(output.len() <= system.max_len(), "{}", output);
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_value() {
+        assert_eq!(small_value("0042", 9999), Some(42));
+        assert_eq!(small_value("0", 9999), None);
+        assert_eq!(small_value("10000", 9999), None);
+        assert_eq!(small_value("99999", 65535), None);
+    }
+}
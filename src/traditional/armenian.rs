@@ -0,0 +1,65 @@
+// Armenian numerals, for the "Ա" format token. The first nine letters are
+// the units, the next nine the tens, and so on up to the thousands, so
+// every digit is at most one letter: "ՌՋՂԹ" is 1000 + 900 + 90 + 9.
+use super::{small_value, TraditionalNumerals};
+
+// the largest value we write in Armenian numerals
+const MAX: u16 = 9999;
+
+// the maximum length in bytes of an Armenian numeral: four letters taking
+// two bytes each
+pub(super) const MAX_LEN: usize = 8;
+
+// the letters for 1 to 9 of each power of ten, from the units up
+const LETTERS: [[char; 9]; 4] = [
+    ['Ա', 'Բ', 'Գ', 'Դ', 'Ե', 'Զ', 'Է', 'Ը', 'Թ'],
+    ['Ժ', 'Ի', 'Լ', 'Խ', 'Ծ', 'Կ', 'Հ', 'Ձ', 'Ղ'],
+    ['Ճ', 'Մ', 'Յ', 'Ն', 'Շ', 'Ո', 'Չ', 'Պ', 'Ջ'],
+    ['Ռ', 'Ս', 'Վ', 'Տ', 'Ր', 'Ց', 'Ւ', 'Փ', 'Ք'],
+];
+
+pub(super) struct Armenian;
+
+impl TraditionalNumerals for Armenian {
+    fn value(&self, digits: &str) -> Option<u16> {
+        small_value(digits, MAX)
+    }
+
+    fn write(&self, value: u16, output: &mut String) {
+        for (power, letters) in LETTERS.iter().enumerate().rev() {
+            let digit = value / 10u16.pow(power as u32) % 10;
+            if digit > 0 {
+                output.push(letters[usize::from(digit) - 1]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn armenian(value: u16) -> String {
+        let mut output = String::new();
+        Armenian.write(value, &mut output);
+        output
+    }
+
+    #[test]
+    fn test_write() {
+        assert_eq!(armenian(1), "Ա");
+        assert_eq!(armenian(10), "Ժ");
+        assert_eq!(armenian(100), "Ճ");
+        assert_eq!(armenian(1000), "Ռ");
+        assert_eq!(armenian(1999), "ՌՋՂԹ");
+        assert_eq!(armenian(2024), "ՍԻԴ");
+        assert_eq!(armenian(9999).len(), MAX_LEN);
+    }
+
+    #[test]
+    fn test_value() {
+        assert_eq!(Armenian.value("9999"), Some(9999));
+        assert_eq!(Armenian.value("0"), None);
+        assert_eq!(Armenian.value("10000"), None);
+    }
+}
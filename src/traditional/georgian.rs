@@ -0,0 +1,74 @@
+// Georgian numerals, for the "ა" format token. Like Armenian ones, there
+// are letters for 1 to 9 of the units, tens, hundreds and thousands, and
+// one more for ten thousand: "ჵჩშჟთ" is 10000 + 1000 + 900 + 90 + 9.
+use super::{small_value, TraditionalNumerals};
+
+// the largest value we write in Georgian numerals
+const MAX: u16 = 19999;
+
+// the maximum length in bytes of a Georgian numeral: five letters taking
+// three bytes each
+pub(super) const MAX_LEN: usize = 15;
+
+const TEN_THOUSAND: char = 'ჵ';
+
+// the letters for 1 to 9 of each power of ten, from the units up
+const LETTERS: [[char; 9]; 4] = [
+    ['ა', 'ბ', 'გ', 'დ', 'ე', 'ვ', 'ზ', 'ჱ', 'თ'],
+    ['ი', 'კ', 'ლ', 'მ', 'ნ', 'ჲ', 'ო', 'პ', 'ჟ'],
+    ['რ', 'ს', 'ტ', 'ჳ', 'ფ', 'ქ', 'ღ', 'ყ', 'შ'],
+    ['ჩ', 'ც', 'ძ', 'წ', 'ჭ', 'ხ', 'ჴ', 'ჯ', 'ჰ'],
+];
+
+pub(super) struct Georgian;
+
+impl TraditionalNumerals for Georgian {
+    fn value(&self, digits: &str) -> Option<u16> {
+        small_value(digits, MAX)
+    }
+
+    fn write(&self, mut value: u16, output: &mut String) {
+        if value >= 10000 {
+            output.push(TEN_THOUSAND);
+            value -= 10000;
+        }
+        for (power, letters) in LETTERS.iter().enumerate().rev() {
+            let digit = value / 10u16.pow(power as u32) % 10;
+            if digit > 0 {
+                output.push(letters[usize::from(digit) - 1]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn georgian(value: u16) -> String {
+        let mut output = String::new();
+        Georgian.write(value, &mut output);
+        output
+    }
+
+    #[test]
+    fn test_write() {
+        assert_eq!(georgian(1), "ა");
+        assert_eq!(georgian(8), "ჱ");
+        assert_eq!(georgian(60), "ჲ");
+        assert_eq!(georgian(400), "ჳ");
+        assert_eq!(georgian(2024), "ცკდ");
+        assert_eq!(georgian(10000), "ჵ");
+        assert_eq!(georgian(11999), "ჵჩშჟთ");
+        assert_eq!(georgian(19999).len(), MAX_LEN);
+    }
+
+    #[test]
+    fn test_value() {
+        assert_eq!(Georgian.value("9999"), Some(9999));
+        assert_eq!(Georgian.value("10000"), Some(10000));
+        assert_eq!(Georgian.value("019999"), Some(19999));
+        assert_eq!(Georgian.value("20000"), None);
+        assert_eq!(Georgian.value("0"), None);
+    }
+}
@@ -0,0 +1,132 @@
+// Traditional Hebrew numbering (gematria), for the "א" format token. The
+// letters add up to the value, from the largest to the smallest: "קכג" is
+// 100 + 20 + 3. Multiple letters get a gershayim (״) before the last one,
+// and a single letter gets a geresh (׳) after it. 15 and 16 are written as
+// 9 + 6 and 9 + 7, as the regular forms spell the name of God.
+//
+// The thousands are a single letter with a geresh in front of the rest, as
+// in years like "ה׳תשפ״ד" (5784). Values that are a whole amount of
+// thousands would look like their amount of thousands, so those are
+// formatted as decimal numbers instead, like values beyond what we write.
+use super::{small_value, TraditionalNumerals};
+
+// the largest value we write in Hebrew numerals
+const MAX: u16 = 9999;
+
+// the maximum length in bytes of a Hebrew numeral, for "ט׳תתקצ״ט" (9999):
+// six letters and two punctuation marks, each taking two bytes
+pub(super) const MAX_LEN: usize = 16;
+
+const UNITS: [char; 9] = ['א', 'ב', 'ג', 'ד', 'ה', 'ו', 'ז', 'ח', 'ט'];
+const TENS: [char; 9] = ['י', 'כ', 'ל', 'מ', 'נ', 'ס', 'ע', 'פ', 'צ'];
+// 500 and up are made from 400 ("ת") and the rest
+const HUNDREDS: [char; 4] = ['ק', 'ר', 'ש', 'ת'];
+
+const GERESH: char = '׳';
+const GERSHAYIM: char = '״';
+
+pub(super) struct Hebrew;
+
+impl TraditionalNumerals for Hebrew {
+    fn value(&self, digits: &str) -> Option<u16> {
+        small_value(digits, MAX).filter(|value| *value < 1000 || value % 1000 != 0)
+    }
+
+    fn write(&self, value: u16, output: &mut String) {
+        write(value, output)
+    }
+}
+
+// append the Hebrew numeral for the value
+fn write(value: u16, output: &mut String) {
+    let (thousands, rest) = (value / 1000, value % 1000);
+    if thousands > 0 {
+        output.push(UNITS[usize::from(thousands) - 1]);
+        output.push(GERESH);
+    }
+    let mut letters = Vec::new();
+    let mut hundreds = rest / 100;
+    while hundreds > 4 {
+        letters.push(HUNDREDS[3]);
+        hundreds -= 4;
+    }
+    if hundreds > 0 {
+        letters.push(HUNDREDS[usize::from(hundreds) - 1]);
+    }
+    match rest % 100 {
+        15 => letters.extend(['ט', 'ו']),
+        16 => letters.extend(['ט', 'ז']),
+        below_hundred => {
+            let (tens, units) = (below_hundred / 10, below_hundred % 10);
+            if tens > 0 {
+                letters.push(TENS[usize::from(tens) - 1]);
+            }
+            if units > 0 {
+                letters.push(UNITS[usize::from(units) - 1]);
+            }
+        }
+    }
+    match letters.split_last() {
+        Some((last, [])) => {
+            output.push(*last);
+            output.push(GERESH);
+        }
+        Some((last, init)) => {
+            output.extend(init);
+            output.push(GERSHAYIM);
+            output.push(*last);
+        }
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hebrew(value: u16) -> String {
+        let mut output = String::new();
+        write(value, &mut output);
+        output
+    }
+
+    #[test]
+    fn test_write() {
+        assert_eq!(hebrew(1), "א׳");
+        assert_eq!(hebrew(10), "י׳");
+        assert_eq!(hebrew(11), "י״א");
+        assert_eq!(hebrew(123), "קכ״ג");
+        assert_eq!(hebrew(400), "ת׳");
+        assert_eq!(hebrew(500), "ת״ק");
+        assert_eq!(hebrew(999), "תתקצ״ט");
+    }
+
+    #[test]
+    fn test_fifteen_and_sixteen() {
+        assert_eq!(hebrew(15), "ט״ו");
+        assert_eq!(hebrew(16), "ט״ז");
+        assert_eq!(hebrew(115), "קט״ו");
+        assert_eq!(hebrew(17), "י״ז");
+    }
+
+    #[test]
+    fn test_thousands() {
+        assert_eq!(hebrew(5784), "ה׳תשפ״ד");
+        assert_eq!(hebrew(1001), "א׳א׳");
+        assert_eq!(hebrew(9999).len(), MAX_LEN);
+    }
+
+    #[test]
+    fn test_value() {
+        assert_eq!(Hebrew.value("1"), Some(1));
+        assert_eq!(Hebrew.value("05784"), Some(5784));
+        assert_eq!(Hebrew.value("0"), None);
+        assert_eq!(Hebrew.value("5000"), None);
+        assert_eq!(Hebrew.value("10000"), None);
+    }
+
+    #[test]
+    fn test_max_len() {
+        assert!((1..=MAX).all(|value| hebrew(value).len() <= MAX_LEN));
+    }
+}
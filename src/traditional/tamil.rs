@@ -0,0 +1,65 @@
+// Traditional Tamil numerals, for the "௧" format token with the "t" format
+// modifier; without it, Tamil digits are a decimal digit family like any
+// other. There are signs for ten (௰), a hundred (௱) and a thousand (௲),
+// each preceded by the digit that counts them unless that's one: "௨௲௩௱௰௫"
+// is 2 × 1000 + 3 × 100 + 10 + 5.
+use super::{small_value, TraditionalNumerals};
+
+// the largest value we write in Tamil numerals
+const MAX: u16 = 9999;
+
+// the maximum length in bytes of a Tamil numeral: four digits and three
+// signs, taking three bytes each
+pub(super) const MAX_LEN: usize = 21;
+
+const DIGITS: [char; 9] = ['௧', '௨', '௩', '௪', '௫', '௬', '௭', '௮', '௯'];
+
+// the signs for a thousand, a hundred and ten, with their values
+const SIGNS: [(u16, char); 3] = [(1000, '௲'), (100, '௱'), (10, '௰')];
+
+pub(super) struct Tamil;
+
+impl TraditionalNumerals for Tamil {
+    fn value(&self, digits: &str) -> Option<u16> {
+        small_value(digits, MAX)
+    }
+
+    fn write(&self, value: u16, output: &mut String) {
+        for (amount, sign) in SIGNS {
+            let count = value / amount % 10;
+            if count > 1 {
+                output.push(DIGITS[usize::from(count) - 1]);
+            }
+            if count > 0 {
+                output.push(sign);
+            }
+        }
+        let units = value % 10;
+        if units > 0 {
+            output.push(DIGITS[usize::from(units) - 1]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tamil(value: u16) -> String {
+        let mut output = String::new();
+        Tamil.write(value, &mut output);
+        output
+    }
+
+    #[test]
+    fn test_write() {
+        assert_eq!(tamil(1), "௧");
+        assert_eq!(tamil(10), "௰");
+        assert_eq!(tamil(11), "௰௧");
+        assert_eq!(tamil(20), "௨௰");
+        assert_eq!(tamil(100), "௱");
+        assert_eq!(tamil(1000), "௲");
+        assert_eq!(tamil(2315), "௨௲௩௱௰௫");
+        assert_eq!(tamil(9999).len(), MAX_LEN);
+    }
+}
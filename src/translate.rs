@@ -0,0 +1,776 @@
+// Translating the number formats of other tools into pictures, for
+// migrating documents that used them. We only format integers, so the
+// parts of a format that deal with fractions, exponents and the like can't
+// be translated; neither can literal text, as integer pictures don't have
+// any. We report the first such feature along with where it starts, so
+// migration tools can point at it.
+//
+// Translated pictures may use extensions (sign placeholders and scaling),
+// so they have to be parsed with extensions when needs_extensions says so.
+//
+// We also translate strftime formats into fn:format-dateTime pictures,
+// which we don't format ourselves, but which hosts implementing that
+// function can use.
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use crate::format_integer::Picture;
+use crate::options::FormatOptions;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Translation {
+    picture: String,
+    needs_extensions: bool,
+}
+
+impl Translation {
+    pub fn picture(&self) -> &str {
+        &self.picture
+    }
+
+    pub fn needs_extensions(&self) -> bool {
+        self.needs_extensions
+    }
+}
+
+// a feature of the format that no picture can express
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Untranslatable {
+    feature: &'static str,
+    position: usize,
+}
+
+impl Untranslatable {
+    fn new(feature: &'static str, position: usize) -> Self {
+        Self { feature, position }
+    }
+
+    // a short description of the feature, like "fraction digits"
+    pub fn feature(&self) -> &'static str {
+        self.feature
+    }
+
+    // where the feature starts in the format, in bytes
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+// translate an Excel number format, like "#,##0". Only the digit
+// placeholders "0" and "#", grouping by "," and scaling by trailing ","
+// translate. As in Excel, a comma anywhere between digit placeholders
+// groups by thousands, so "#,0" is "#,##0".
+pub fn from_excel(format: &str) -> Result<Translation, Untranslatable> {
+    let mut mandatory_digits = 0;
+    let (mut grouping, mut scale) = (false, 0);
+    let mut chars = format.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        match c {
+            '0' => mandatory_digits += 1,
+            '#' => {}
+            ',' if groups(&chars) => grouping = true,
+            ',' => scale += 1,
+            ';' => return Err(Untranslatable::new("sections", position)),
+            '.' => return Err(Untranslatable::new("fraction digits", position)),
+            '%' => return Err(Untranslatable::new("percentage", position)),
+            'E' | 'e' => return Err(Untranslatable::new("exponent", position)),
+            '?' => return Err(Untranslatable::new("space padding", position)),
+            '_' | '*' => return Err(Untranslatable::new("padding", position)),
+            '[' => return Err(Untranslatable::new("color or condition", position)),
+            _ => return Err(Untranslatable::new("literal text", position)),
+        }
+    }
+    let mut picture = String::new();
+    push_digits(&mut picture, mandatory_digits, grouping.then_some(3));
+    picture.extend(std::iter::repeat_n(',', scale));
+    translation(picture, scale > 0, format.len())
+}
+
+// translate a printf conversion specification for integers, like "%08d".
+// The flags "0", "+" and "'" translate, and so do the width with zero
+// padding and the precision. Unlike printf, pictures don't count the sign
+// towards the width.
+pub fn from_printf(format: &str) -> Result<Translation, Untranslatable> {
+    let mut chars = format.char_indices().peekable();
+    match chars.next() {
+        Some((_, '%')) => {}
+        Some((position, _)) => return Err(Untranslatable::new("literal text", position)),
+        None => return Err(Untranslatable::new("conversion", 0)),
+    }
+    let (mut zero_padding, mut always_signed, mut grouping) = (false, false, false);
+    while let Some((position, c)) = chars.peek().copied() {
+        match c {
+            '0' => zero_padding = true,
+            '+' => always_signed = true,
+            '\'' => grouping = true,
+            '-' => return Err(Untranslatable::new("left justification", position)),
+            ' ' => return Err(Untranslatable::new("space sign", position)),
+            '#' => return Err(Untranslatable::new("alternate form", position)),
+            _ => break,
+        }
+        chars.next();
+    }
+    let width_position = chars.peek().map_or(format.len(), |(position, _)| *position);
+    let width = read_number(&mut chars);
+    let precision = match chars.peek().copied() {
+        Some((_, '.')) => {
+            chars.next();
+            // a lone "." is a precision of zero
+            Some(read_number(&mut chars).unwrap_or(0))
+        }
+        _ => None,
+    };
+    // length modifiers don't matter, as we format integers of any size
+    while let Some((_, 'h' | 'l' | 'j' | 'z' | 't' | 'q')) = chars.peek() {
+        chars.next();
+    }
+    match chars.next() {
+        Some((_, 'd' | 'i' | 'u')) => {}
+        Some((position, _)) => return Err(Untranslatable::new("conversion", position)),
+        None => return Err(Untranslatable::new("conversion", format.len())),
+    }
+    if let Some((position, _)) = chars.next() {
+        return Err(Untranslatable::new("literal text", position));
+    }
+    // the precision is the minimum amount of digits, and overrides zero
+    // padding; without either there's at least one digit
+    let digits = match (precision, width) {
+        (Some(precision), _) => precision,
+        (None, Some(width)) if zero_padding => width,
+        (None, Some(_)) => return Err(Untranslatable::new("space padding", width_position)),
+        (None, None) => 1,
+    };
+    // a zero precision formats zero as nothing, which pictures can't do
+    if digits == 0 {
+        return Err(Untranslatable::new("zero precision", width_position));
+    }
+    let mut picture = String::new();
+    if always_signed {
+        picture.push('+');
+    }
+    push_digits(&mut picture, digits, grouping.then_some(3));
+    translation(picture, always_signed, format.len())
+}
+
+// translate a Java DecimalFormat pattern, like "#,##0". Quoted text is
+// literal text, which doesn't translate, and neither do fractions,
+// exponents and percentages. Currency signs translate to currency
+// placeholders. A negative subpattern only translates if it's what we do
+// anyway: the positive one with a minus sign in front.
+pub fn from_java(pattern: &str) -> Result<Translation, Untranslatable> {
+    let tokens = java_tokens(pattern)?;
+    let (positive, negative) = match tokens.iter().position(|t| t.is_special(';')) {
+        Some(index) => (&tokens[..index], Some(&tokens[index + 1..])),
+        None => (tokens.as_slice(), None),
+    };
+    let positive_parts = java_parts(positive, pattern.len())?;
+    if let Some(negative) = negative {
+        let position = negative.first().map_or(pattern.len(), |t| t.position);
+        // only the prefix and the suffix of a negative subpattern count
+        let is_minus = |t: &JavaToken| t.c == '-' && !t.quoted;
+        let parts = match negative.split_first() {
+            Some((minus, rest)) if is_minus(minus) => java_parts(rest, pattern.len()).ok(),
+            _ => None,
+        };
+        let same_affixes = parts.is_some_and(|parts| {
+            same_chars(parts.prefix, positive_parts.prefix)
+                && same_chars(parts.suffix, positive_parts.suffix)
+        });
+        if !same_affixes {
+            return Err(Untranslatable::new("negative subpattern", position));
+        }
+    }
+    let mut picture = String::new();
+    let prefix = java_currency(positive_parts.prefix, false)?;
+    let suffix = java_currency(positive_parts.suffix, true)?;
+    picture.push_str(&prefix);
+    push_digits(
+        &mut picture,
+        positive_parts.mandatory_digits.max(1),
+        positive_parts.group_size,
+    );
+    picture.push_str(&suffix);
+    let needs_extensions = !prefix.is_empty() || !suffix.is_empty();
+    translation(picture, needs_extensions, pattern.len())
+}
+
+// translate a .NET numeric format string, either a standard one like "N0"
+// or a custom one like "#,0". Standard formats translate when they produce
+// integers without culture-specific symbols: "D", "N", "F", "G" and "R",
+// without fraction digits. Custom formats translate like Excel ones,
+// except that optional fraction digits, which integers don't have, are
+// dropped.
+pub fn from_dotnet(format: &str) -> Result<Translation, Untranslatable> {
+    let mut chars = format.chars();
+    if let (Some(specifier), precision) = (chars.next(), chars.as_str()) {
+        if specifier.is_ascii_alphabetic()
+            && precision.len() <= 2
+            && precision.bytes().all(|b| b.is_ascii_digit())
+        {
+            return dotnet_standard(specifier, precision);
+        }
+    }
+    let mut mandatory_digits = 0;
+    let (mut grouping, mut scale) = (false, 0);
+    let mut chars = format.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        match c {
+            '0' => mandatory_digits += 1,
+            '#' => {}
+            ',' if groups(&chars) => grouping = true,
+            ',' => scale += 1,
+            '.' => {
+                if let Some((position, _)) = chars.find(|(_, c)| *c != '#') {
+                    return Err(Untranslatable::new("fraction digits", position));
+                }
+            }
+            ';' => return Err(Untranslatable::new("sections", position)),
+            '%' => return Err(Untranslatable::new("percentage", position)),
+            '‰' => return Err(Untranslatable::new("per mille", position)),
+            'E' | 'e' => return Err(Untranslatable::new("exponent", position)),
+            _ => return Err(Untranslatable::new("literal text", position)),
+        }
+    }
+    let mut picture = String::new();
+    push_digits(&mut picture, mandatory_digits, grouping.then_some(3));
+    picture.extend(std::iter::repeat_n(',', scale));
+    translation(picture, scale > 0, format.len())
+}
+
+fn dotnet_standard(specifier: char, precision: &str) -> Result<Translation, Untranslatable> {
+    // the precision comes right after the specifier
+    let precision_position = specifier.len_utf8();
+    let precision: Option<usize> = precision.parse().ok();
+    let has_fraction = precision.is_some_and(|precision| precision > 0);
+    let mut picture = String::new();
+    match specifier.to_ascii_uppercase() {
+        // the precision is the minimum amount of digits
+        'D' => push_digits(&mut picture, precision.unwrap_or(1).max(1), None),
+        // these have two fraction digits unless the precision says otherwise
+        'N' | 'F' if has_fraction || precision.is_none() => {
+            return Err(Untranslatable::new("fraction digits", precision_position))
+        }
+        'N' => push_digits(&mut picture, 1, Some(3)),
+        'F' => push_digits(&mut picture, 1, None),
+        // integers are written in full, unless a precision asks for fewer
+        // significant digits, which switches to an exponent
+        'G' | 'R' if !has_fraction => push_digits(&mut picture, 1, None),
+        'G' | 'R' => return Err(Untranslatable::new("exponent", precision_position)),
+        'C' => return Err(Untranslatable::new("currency pattern", 0)),
+        'P' => return Err(Untranslatable::new("percentage", 0)),
+        'E' => return Err(Untranslatable::new("exponent", 0)),
+        'X' => return Err(Untranslatable::new("hexadecimal", 0)),
+        'B' => return Err(Untranslatable::new("binary", 0)),
+        _ => return Err(Untranslatable::new("format specifier", 0)),
+    }
+    translation(picture, false, specifier.len_utf8())
+}
+
+// translate a strftime format, like "%Y-%m-%d %H:%M", into a picture for
+// fn:format-dateTime. Conversions that depend on the locale (like "%c") or
+// that pictures have no component for (like week numbers) don't translate.
+pub fn from_strftime(format: &str) -> Result<String, Untranslatable> {
+    let mut picture = String::new();
+    let mut chars = format.char_indices();
+    while let Some((position, c)) = chars.next() {
+        if c != '%' {
+            // brackets are doubled to make them literal
+            if matches!(c, '[' | ']') {
+                picture.push(c);
+            }
+            picture.push(c);
+            continue;
+        }
+        let Some((_, conversion)) = chars.next() else {
+            return Err(Untranslatable::new("conversion", position));
+        };
+        let translated = match conversion {
+            'Y' => "[Y0001]",
+            'y' => "[Y01]",
+            'm' => "[M01]",
+            'd' => "[D01]",
+            'j' => "[d001]",
+            'H' => "[H01]",
+            'I' => "[h01]",
+            'M' => "[m01]",
+            'S' => "[s01]",
+            'p' => "[PN]",
+            'B' => "[MNn]",
+            'b' | 'h' => "[MNn,3-3]",
+            'A' => "[FNn]",
+            'a' => "[FNn,3-3]",
+            'u' => "[F1]",
+            'z' => "[Z0000]",
+            'Z' => "[ZN]",
+            'F' => "[Y0001]-[M01]-[D01]",
+            'T' => "[H01]:[m01]:[s01]",
+            'R' => "[H01]:[m01]",
+            'D' => "[M01]/[D01]/[Y01]",
+            '%' => "%",
+            'n' => "\n",
+            't' => "\t",
+            'e' | 'k' | 'l' => return Err(Untranslatable::new("space padding", position)),
+            'c' | 'x' | 'X' | 'r' | 'E' | 'O' => {
+                return Err(Untranslatable::new("locale format", position))
+            }
+            'U' | 'W' | 'V' | 'G' | 'g' => {
+                return Err(Untranslatable::new("week number", position))
+            }
+            'w' => return Err(Untranslatable::new("weekday from sunday", position)),
+            _ => return Err(Untranslatable::new("conversion", position)),
+        };
+        picture.push_str(translated);
+    }
+    Ok(picture)
+}
+
+// a character of a Java pattern, where quoted characters are never special
+#[derive(Debug, Clone, Copy)]
+struct JavaToken {
+    position: usize,
+    c: char,
+    quoted: bool,
+}
+
+impl JavaToken {
+    fn is_special(&self, c: char) -> bool {
+        !self.quoted && self.c == c
+    }
+
+    fn is_number(&self) -> bool {
+        !self.quoted && matches!(self.c, '#' | '0' | ',' | '.')
+    }
+}
+
+fn java_tokens(pattern: &str) -> Result<Vec<JavaToken>, Untranslatable> {
+    let mut tokens = Vec::new();
+    let mut quote_start = None;
+    let mut chars = pattern.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        if c != '\'' {
+            tokens.push(JavaToken {
+                position,
+                c,
+                quoted: quote_start.is_some(),
+            });
+        } else if let Some((_, '\'')) = chars.peek() {
+            // a doubled quote is a quote, inside quotes or not
+            chars.next();
+            tokens.push(JavaToken {
+                position,
+                c,
+                quoted: true,
+            });
+        } else {
+            quote_start = match quote_start {
+                Some(_) => None,
+                None => Some(position),
+            };
+        }
+    }
+    match quote_start {
+        Some(position) => Err(Untranslatable::new("unterminated quote", position)),
+        None => Ok(tokens),
+    }
+}
+
+struct JavaParts<'a> {
+    prefix: &'a [JavaToken],
+    mandatory_digits: usize,
+    // the amount of digits between grouping separators, if any
+    group_size: Option<usize>,
+    suffix: &'a [JavaToken],
+}
+
+// split a subpattern into its prefix, number and suffix
+fn java_parts(tokens: &[JavaToken], end: usize) -> Result<JavaParts<'_>, Untranslatable> {
+    let Some(start) = tokens.iter().position(JavaToken::is_number) else {
+        return Err(Untranslatable::new("digits", end));
+    };
+    let last = tokens
+        .iter()
+        .rposition(JavaToken::is_number)
+        .unwrap_or(start);
+    let mut mandatory_digits = 0;
+    let mut group_size = None;
+    for token in &tokens[start..=last] {
+        match (token.quoted, token.c) {
+            (false, '0') => mandatory_digits += 1,
+            (false, '#') => {}
+            (false, ',') => {
+                group_size = Some(0);
+                continue;
+            }
+            (false, '.') => return Err(Untranslatable::new("fraction digits", token.position)),
+            (false, 'E') => return Err(Untranslatable::new("exponent", token.position)),
+            _ => return Err(Untranslatable::new("literal text", token.position)),
+        }
+        group_size = group_size.map(|size| size + 1);
+    }
+    if group_size == Some(0) {
+        return Err(Untranslatable::new("grouping", tokens[last].position));
+    }
+    Ok(JavaParts {
+        prefix: &tokens[..start],
+        mandatory_digits,
+        group_size,
+        suffix: &tokens[last + 1..],
+    })
+}
+
+fn same_chars(a: &[JavaToken], b: &[JavaToken]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| a.c == b.c && a.quoted == b.quoted)
+}
+
+// translate a prefix or suffix, which can only be a currency sign or two,
+// with a space between them and the number
+fn java_currency(affix: &[JavaToken], is_suffix: bool) -> Result<String, Untranslatable> {
+    let mut placeholder = String::new();
+    let mut chars = String::new();
+    for token in affix {
+        if token.quoted {
+            return Err(Untranslatable::new("literal text", token.position));
+        }
+        match token.c {
+            '¤' => placeholder.push('¤'),
+            ' ' | '\u{a0}' => {}
+            '%' => return Err(Untranslatable::new("percentage", token.position)),
+            '‰' => return Err(Untranslatable::new("per mille", token.position)),
+            _ => return Err(Untranslatable::new("literal text", token.position)),
+        }
+        chars.push(token.c);
+    }
+    // the translated placeholder must be exactly what the picture accepts
+    let spaced = |space: char| {
+        if is_suffix {
+            format!("{}{}", space, placeholder)
+        } else {
+            format!("{}{}", placeholder, space)
+        }
+    };
+    if chars.is_empty()
+        || (placeholder.len() <= "¤¤".len()
+            && (chars == placeholder || chars == spaced(' ') || chars == spaced('\u{a0}')))
+    {
+        Ok(chars)
+    } else {
+        let position = affix.first().map_or(0, |t| t.position);
+        Err(Untranslatable::new("literal text", position))
+    }
+}
+
+// append the given amount of mandatory digits, with a separator every
+// group_size digits. There are optional digits before them if needed to
+// have a separator to repeat.
+fn push_digits(picture: &mut String, mandatory_digits: usize, group_size: Option<usize>) {
+    let Some(group_size) = group_size else {
+        picture.extend(std::iter::repeat_n('0', mandatory_digits));
+        return;
+    };
+    let total = mandatory_digits.max(group_size + 1);
+    for index in 0..total {
+        if index > 0 && (total - index).is_multiple_of(group_size) {
+            picture.push(',');
+        }
+        picture.push(if total - index > mandatory_digits {
+            '#'
+        } else {
+            '0'
+        });
+    }
+}
+
+// whether the comma before the rest groups, which it does if digit
+// placeholders follow, maybe after more commas; any other comma scales
+fn groups(rest: &Peekable<CharIndices>) -> bool {
+    rest.clone()
+        .map(|(_, c)| c)
+        .find(|c| *c != ',')
+        .is_some_and(|c| matches!(c, '0' | '#'))
+}
+
+// read a decimal number, if there is one
+fn read_number(chars: &mut Peekable<CharIndices>) -> Option<usize> {
+    let mut n: Option<usize> = None;
+    while let Some(digit) = chars.peek().and_then(|(_, c)| c.to_digit(10)) {
+        n = Some(
+            n.unwrap_or(0)
+                .saturating_mul(10)
+                .saturating_add(digit as usize),
+        );
+        chars.next();
+    }
+    n
+}
+
+// check that what we produced is a valid picture, which it isn't if the
+// format has no mandatory digit, for instance
+fn translation(
+    picture: String,
+    needs_extensions: bool,
+    end: usize,
+) -> Result<Translation, Untranslatable> {
+    let options = FormatOptions::builder()
+        .extensions(needs_extensions)
+        .build()
+        .map_err(|_| Untranslatable::new("digits", end))?;
+    Picture::parse_with_options(&picture, &options)
+        .map_err(|_| Untranslatable::new("digits", end))?;
+    Ok(Translation {
+        picture,
+        needs_extensions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_integer::format_integer_with_options;
+
+    fn format(translation: &Translation, i: i64) -> String {
+        let options = FormatOptions::builder()
+            .extensions(translation.needs_extensions())
+            .build()
+            .unwrap();
+        format_integer_with_options(i.into(), translation.picture(), &options).unwrap()
+    }
+
+    #[test]
+    fn test_excel() {
+        let translation = from_excel("#,##0").unwrap();
+        assert_eq!(translation.picture(), "#,##0");
+        assert!(!translation.needs_extensions());
+        assert_eq!(format(&translation, 1234567), "1,234,567");
+        assert_eq!(from_excel("0000").unwrap().picture(), "0000");
+    }
+
+    #[test]
+    fn test_excel_comma_anywhere_groups() {
+        let translation = from_excel("#,0").unwrap();
+        assert_eq!(translation.picture(), "#,##0");
+        assert_eq!(format(&translation, 1234567), "1,234,567");
+        let translation = from_excel("00,00").unwrap();
+        assert_eq!(translation.picture(), "0,000");
+        assert_eq!(format(&translation, 1234), "1,234");
+        assert_eq!(format(&translation, 12), "0,012");
+    }
+
+    #[test]
+    fn test_excel_scaling() {
+        let translation = from_excel("#,##0,").unwrap();
+        assert!(translation.needs_extensions());
+        assert_eq!(format(&translation, 1234567), "1,235");
+    }
+
+    #[test]
+    fn test_excel_untranslatable() {
+        assert_eq!(
+            from_excel("#,##0.00;[Red](#,##0.00)"),
+            Err(Untranslatable::new("fraction digits", 5))
+        );
+        assert_eq!(
+            from_excel("#,##0;[Red]-#,##0"),
+            Err(Untranslatable::new("sections", 5))
+        );
+        assert_eq!(
+            from_excel("0 \"items\""),
+            Err(Untranslatable::new("literal text", 1))
+        );
+        assert_eq!(from_excel("#"), Err(Untranslatable::new("digits", 1)));
+    }
+
+    #[test]
+    fn test_printf() {
+        assert_eq!(from_printf("%d").unwrap().picture(), "0");
+        assert_eq!(from_printf("%08d").unwrap().picture(), "00000000");
+        assert_eq!(from_printf("%.3lld").unwrap().picture(), "000");
+        let translation = from_printf("%+d").unwrap();
+        assert!(translation.needs_extensions());
+        assert_eq!(format(&translation, 5), "+5");
+    }
+
+    #[test]
+    fn test_printf_grouping() {
+        let translation = from_printf("%'d").unwrap();
+        assert_eq!(translation.picture(), "#,##0");
+        assert_eq!(format(&translation, 1234567), "1,234,567");
+        assert_eq!(from_printf("%'07d").unwrap().picture(), "0,000,000");
+    }
+
+    #[test]
+    fn test_printf_untranslatable() {
+        assert_eq!(
+            from_printf("%08.2f"),
+            Err(Untranslatable::new("conversion", 5))
+        );
+        assert_eq!(
+            from_printf("%8d"),
+            Err(Untranslatable::new("space padding", 1))
+        );
+        assert_eq!(
+            from_printf("%-d"),
+            Err(Untranslatable::new("left justification", 1))
+        );
+        assert_eq!(
+            from_printf("%d items"),
+            Err(Untranslatable::new("literal text", 2))
+        );
+        assert_eq!(
+            from_printf("%.0d"),
+            Err(Untranslatable::new("zero precision", 1))
+        );
+    }
+
+    #[test]
+    fn test_java() {
+        assert_eq!(from_java("#,##0").unwrap().picture(), "#,##0");
+        assert_eq!(from_java("#").unwrap().picture(), "0");
+        assert_eq!(from_java("0000").unwrap().picture(), "0000");
+        // only the last grouping counts, as in Java
+        let translation = from_java("#,##,###").unwrap();
+        assert_eq!(translation.picture(), "#,##0");
+        assert_eq!(format(&translation, 1234567), "1,234,567");
+        assert_eq!(from_java("#,##0;-#,##0").unwrap().picture(), "#,##0");
+    }
+
+    #[test]
+    fn test_java_currency() {
+        let translation = from_java("¤#,##0").unwrap();
+        assert_eq!(translation.picture(), "¤#,##0");
+        assert!(translation.needs_extensions());
+        assert_eq!(from_java("#,##0 ¤¤").unwrap().picture(), "#,##0 ¤¤");
+        assert_eq!(
+            from_java("#,##0 ¤ ¤"),
+            Err(Untranslatable::new("literal text", 5))
+        );
+    }
+
+    #[test]
+    fn test_java_untranslatable() {
+        assert_eq!(
+            from_java("#,##0.00"),
+            Err(Untranslatable::new("fraction digits", 5))
+        );
+        assert_eq!(from_java("0E0"), Err(Untranslatable::new("exponent", 1)));
+        assert_eq!(
+            from_java("#,##0%"),
+            Err(Untranslatable::new("percentage", 5))
+        );
+        assert_eq!(
+            from_java("#,##0;(#,##0)"),
+            Err(Untranslatable::new("negative subpattern", 6))
+        );
+        assert_eq!(from_java("#,##0,"), Err(Untranslatable::new("grouping", 5)));
+    }
+
+    #[test]
+    fn test_java_quoting() {
+        assert_eq!(
+            from_java("'#'0"),
+            Err(Untranslatable::new("literal text", 1))
+        );
+        assert_eq!(
+            from_java("0''"),
+            Err(Untranslatable::new("literal text", 1))
+        );
+        assert_eq!(
+            from_java("0 'x"),
+            Err(Untranslatable::new("unterminated quote", 2))
+        );
+        // quoted special characters aren't special
+        assert_eq!(
+            from_java("0';'"),
+            Err(Untranslatable::new("literal text", 2))
+        );
+    }
+
+    #[test]
+    fn test_dotnet_standard() {
+        assert_eq!(from_dotnet("N0").unwrap().picture(), "#,##0");
+        assert_eq!(from_dotnet("D8").unwrap().picture(), "00000000");
+        assert_eq!(from_dotnet("d").unwrap().picture(), "0");
+        assert_eq!(from_dotnet("G").unwrap().picture(), "0");
+        assert_eq!(from_dotnet("G3"), Err(Untranslatable::new("exponent", 1)));
+        assert_eq!(
+            from_dotnet("N2"),
+            Err(Untranslatable::new("fraction digits", 1))
+        );
+        assert_eq!(
+            from_dotnet("N"),
+            Err(Untranslatable::new("fraction digits", 1))
+        );
+        assert_eq!(
+            from_dotnet("X4"),
+            Err(Untranslatable::new("hexadecimal", 0))
+        );
+        assert_eq!(
+            from_dotnet("C"),
+            Err(Untranslatable::new("currency pattern", 0))
+        );
+    }
+
+    #[test]
+    fn test_dotnet_custom() {
+        let translation = from_dotnet("#,0.##").unwrap();
+        assert_eq!(translation.picture(), "#,##0");
+        assert_eq!(format(&translation, 1234567), "1,234,567");
+        assert_eq!(from_dotnet("0000").unwrap().picture(), "0000");
+        // a comma anywhere among the digits groups by three
+        assert_eq!(from_dotnet("#,#00").unwrap().picture(), "#,#00");
+        let translation = from_dotnet("#,0,,").unwrap();
+        assert!(translation.needs_extensions());
+        assert_eq!(format(&translation, 1_234_567_890), "1,235");
+    }
+
+    #[test]
+    fn test_dotnet_untranslatable() {
+        assert_eq!(
+            from_dotnet("#,0.00"),
+            Err(Untranslatable::new("fraction digits", 4))
+        );
+        assert_eq!(
+            from_dotnet("#,0;(#,0)"),
+            Err(Untranslatable::new("sections", 3))
+        );
+        assert_eq!(
+            from_dotnet("0 kg"),
+            Err(Untranslatable::new("literal text", 1))
+        );
+    }
+
+    #[test]
+    fn test_strftime() {
+        assert_eq!(
+            from_strftime("%Y-%m-%d %H:%M").unwrap(),
+            "[Y0001]-[M01]-[D01] [H01]:[m01]"
+        );
+        assert_eq!(from_strftime("%A, %B %d").unwrap(), "[FNn], [MNn] [D01]");
+        assert_eq!(
+            from_strftime("[%T] 100%%").unwrap(),
+            "[[[H01]:[m01]:[s01]]] 100%"
+        );
+    }
+
+    #[test]
+    fn test_strftime_untranslatable() {
+        assert_eq!(
+            from_strftime("%c"),
+            Err(Untranslatable::new("locale format", 0))
+        );
+        assert_eq!(
+            from_strftime("week %V"),
+            Err(Untranslatable::new("week number", 5))
+        );
+        assert_eq!(
+            from_strftime("%e %b"),
+            Err(Untranslatable::new("space padding", 0))
+        );
+        assert_eq!(
+            from_strftime("100%"),
+            Err(Untranslatable::new("conversion", 3))
+        );
+    }
+}
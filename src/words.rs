@@ -0,0 +1,434 @@
+// Spelling out numbers as words, for the "w" format token. We work on the
+// ascii digits of the number, so that there's no limit on its size.
+use std::fmt;
+use std::sync::Arc;
+
+use icu::locid::{langid, subtags::region, LanguageIdentifier};
+
+use crate::language::Language;
+
+mod english;
+mod german;
+mod korean;
+
+// no digit takes more than this many bytes to spell out, in any language.
+// The worst case is a group like "seven hundred and seventy-seven
+// quadrillion", which is less than 15 bytes per digit; a lone leading digit
+// ("seven quadrillion") takes the rest.
+const MAX_LEN_PER_DIGIT: usize = 18;
+
+// the maximum length in bytes of the words for a number with the given
+// amount of digits, not counting the sign
+pub(crate) const fn max_len(digit_count: usize) -> usize {
+    let digit_count = if digit_count == 0 { 1 } else { digit_count };
+    digit_count.saturating_mul(MAX_LEN_PER_DIGIT)
+}
+
+// ordinal words are at most this many bytes longer than cardinal ones, as
+// in German "zwanzig" and "zwanzigster"
+pub(crate) const ORDINAL_MAX_EXTRA_LEN: usize = 4;
+
+// the maximum length in bytes of the suffix of an ordinal number in
+// digits, as in Korean "번째"
+pub(crate) const ORDINAL_SUFFIX_LEN: usize = korean::ORDINAL_SUFFIX.len();
+
+// what a negative number starts with in English and German
+const NEGATIVE_WORD: &str = "minus ";
+
+// the maximum length in bytes of those words
+pub(crate) const NEGATIVE_WORD_MAX_LEN: usize = korean::NEGATIVE_WORD.len();
+
+// The languages we can spell out numbers in. Other languages fall back
+// along their CLDR fallback chain, so "de-AT" is spelled out in German;
+// if nothing on the chain is supported, as for "pt-BR", we fall back to
+// English.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WordLanguage {
+    // English as in Britain and most of the world, with "and" before the
+    // tens: "one hundred and one"
+    English,
+    // English as in the United States, without the "and": "one hundred one"
+    AmericanEnglish,
+    German,
+    // German as in Switzerland and Liechtenstein, which write "ss" for "ß":
+    // "dreissig"
+    SwissGerman,
+    // Korean in the Sino-Korean numerals
+    Korean,
+    // Korean in the native numerals, for the traditional format modifier
+    NativeKorean,
+}
+
+impl WordLanguage {
+    // the words for a language on a fallback chain, if we have them;
+    // traditional is whether the traditional format modifier was given,
+    // which only matters for Korean
+    fn for_candidate(candidate: &Language, traditional: bool) -> Option<Self> {
+        let identifier = candidate.identifier();
+        if identifier.script.is_some() {
+            return None;
+        }
+        match (identifier.language.as_str(), identifier.region) {
+            ("de", Some(region)) if region == region!("CH") || region == region!("LI") => {
+                Some(WordLanguage::SwissGerman)
+            }
+            // we don't have other regional words; the region is dropped
+            // further along the chain
+            (_, Some(_)) => None,
+            ("en", None) => Some(WordLanguage::English),
+            ("de", None) => Some(WordLanguage::German),
+            ("ko", None) if traditional => Some(WordLanguage::NativeKorean),
+            ("ko", None) => Some(WordLanguage::Korean),
+            _ => None,
+        }
+    }
+
+    // whether the words depend on the region of the language
+    pub(crate) fn is_regional(self) -> bool {
+        match self {
+            WordLanguage::AmericanEnglish | WordLanguage::SwissGerman => true,
+            WordLanguage::English
+            | WordLanguage::German
+            | WordLanguage::Korean
+            | WordLanguage::NativeKorean => false,
+        }
+    }
+
+    // the region decides the style of English, wherever it comes from
+    fn in_region_of(self, language: &Language) -> Self {
+        if self == WordLanguage::English && language.identifier().region == Some(region!("US")) {
+            WordLanguage::AmericanEnglish
+        } else {
+            self
+        }
+    }
+
+    // the language the words are in, which decides how they're cased
+    pub(crate) fn identifier(self) -> LanguageIdentifier {
+        match self {
+            WordLanguage::English | WordLanguage::AmericanEnglish => langid!("en"),
+            WordLanguage::German | WordLanguage::SwissGerman => langid!("de"),
+            WordLanguage::Korean | WordLanguage::NativeKorean => langid!("ko"),
+        }
+    }
+
+    // what a negative number starts with, including the space after it
+    pub(crate) fn negative_word(self) -> &'static str {
+        match self {
+            WordLanguage::English
+            | WordLanguage::AmericanEnglish
+            | WordLanguage::German
+            | WordLanguage::SwissGerman => NEGATIVE_WORD,
+            WordLanguage::Korean | WordLanguage::NativeKorean => korean::NEGATIVE_WORD,
+        }
+    }
+
+    // append the cardinal number for the ascii digits to output
+    pub(crate) fn write_cardinal(self, digits: &str, output: &mut String) {
+        match self {
+            WordLanguage::English => english::write_cardinal(digits, true, output),
+            WordLanguage::AmericanEnglish => english::write_cardinal(digits, false, output),
+            WordLanguage::German => german::write_cardinal(digits, true, output),
+            WordLanguage::SwissGerman => german::write_cardinal(digits, false, output),
+            WordLanguage::Korean => korean::write_cardinal(digits, output),
+            WordLanguage::NativeKorean => korean::write_native_cardinal(digits, output),
+        }
+    }
+
+    // append the ordinal number for the ascii digits to output, in the
+    // variation given by the format modifier, if the language has it
+    pub(crate) fn write_ordinal(self, digits: &str, variation: Option<&str>, output: &mut String) {
+        match self {
+            WordLanguage::English => english::write_ordinal(digits, true, output),
+            WordLanguage::AmericanEnglish => english::write_ordinal(digits, false, output),
+            WordLanguage::German => german::write_ordinal(digits, variation, true, output),
+            WordLanguage::SwissGerman => german::write_ordinal(digits, variation, false, output),
+            WordLanguage::Korean => korean::write_ordinal(digits, output),
+            WordLanguage::NativeKorean => korean::write_native_ordinal(digits, output),
+        }
+    }
+
+    // the suffix for an ordinal number written in digits
+    pub(crate) fn ordinal_suffix(self, digits: &str) -> &'static str {
+        match self {
+            WordLanguage::English | WordLanguage::AmericanEnglish => {
+                english::ordinal_suffix(digits)
+            }
+            WordLanguage::German | WordLanguage::SwissGerman => german::ORDINAL_SUFFIX,
+            WordLanguage::Korean | WordLanguage::NativeKorean => korean::ORDINAL_SUFFIX,
+        }
+    }
+}
+
+// Words for numbers in a language we don't ship, like Welsh or Icelandic,
+// which hosts register with FormatOptionsBuilder::number_speller. The
+// digits are the ascii digits of the absolute value, without leading
+// zeros, so there's no limit on the size of the number. Words should take
+// no more than 18 bytes per digit, as ours do, or
+// Picture::max_formatted_len underestimates them.
+pub trait NumberSpeller: Send + Sync {
+    // append the cardinal number to output
+    fn write_cardinal(&self, digits: &str, output: &mut String);
+
+    // append the ordinal number to output, in the variation given by the
+    // format modifier, as in "o(-e)", if any. Languages without ordinal
+    // words can leave this to write the cardinal number.
+    fn write_ordinal(&self, digits: &str, variation: Option<&str>, output: &mut String) {
+        let _ = variation;
+        self.write_cardinal(digits, output);
+    }
+
+    // what a negative number starts with, including any space after it
+    fn negative_word(&self) -> &str;
+
+    // the suffix for an ordinal number written in digits, as in "1;o"
+    fn ordinal_suffix(&self, digits: &str) -> &str {
+        let _ = digits;
+        ""
+    }
+}
+
+// a speller registered for a language. Options with spellers are only
+// equal if they share the very same spellers.
+#[derive(Clone)]
+pub(crate) struct Speller(pub(crate) Arc<dyn NumberSpeller>);
+
+impl fmt::Debug for Speller {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Speller")
+    }
+}
+
+impl PartialEq for Speller {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+// the words to spell out numbers in: ours, or those of a registered speller
+// with the language it was registered for
+#[derive(Clone, Copy)]
+pub(crate) enum Spelling<'a> {
+    Builtin(WordLanguage),
+    Custom(&'a Language, &'a dyn NumberSpeller),
+}
+
+impl<'a> Spelling<'a> {
+    // the words for the language, going along its fallback chain, where
+    // registered spellers come before our words for the same language.
+    // Traditional is whether the traditional format modifier was given.
+    pub(crate) fn new(
+        language: Option<&Language>,
+        spellers: &'a [(Language, Speller)],
+        traditional: bool,
+    ) -> Self {
+        let Some(language) = language else {
+            return Spelling::Builtin(WordLanguage::English);
+        };
+        for candidate in language.fallback_chain() {
+            if let Some((registered, speller)) = spellers.iter().find(|(l, _)| *l == candidate) {
+                return Spelling::Custom(registered, speller.0.as_ref());
+            }
+            if let Some(words) = WordLanguage::for_candidate(&candidate, traditional) {
+                return Spelling::Builtin(words.in_region_of(language));
+            }
+        }
+        Spelling::Builtin(WordLanguage::English.in_region_of(language))
+    }
+
+    // the language the numbers are spelled out in, for the language they
+    // were asked for: the one a speller was registered for, or ours, which
+    // keeps the region if the words depend on it
+    pub(crate) fn language(self, language: Option<&Language>) -> Language {
+        match self {
+            Spelling::Builtin(words) => {
+                let mut identifier = words.identifier();
+                if words.is_regional() {
+                    identifier.region = language.and_then(Language::region);
+                }
+                Language::new(identifier)
+            }
+            Spelling::Custom(registered, _) => registered.clone(),
+        }
+    }
+
+    pub(crate) fn identifier(self) -> LanguageIdentifier {
+        match self {
+            Spelling::Builtin(words) => words.identifier(),
+            Spelling::Custom(language, _) => language.identifier().clone(),
+        }
+    }
+
+    pub(crate) fn negative_word(self) -> &'a str {
+        match self {
+            Spelling::Builtin(words) => words.negative_word(),
+            Spelling::Custom(_, speller) => speller.negative_word(),
+        }
+    }
+
+    pub(crate) fn write_cardinal(self, digits: &str, output: &mut String) {
+        match self {
+            Spelling::Builtin(words) => words.write_cardinal(digits, output),
+            Spelling::Custom(_, speller) => speller.write_cardinal(digits, output),
+        }
+    }
+
+    pub(crate) fn write_ordinal(self, digits: &str, variation: Option<&str>, output: &mut String) {
+        match self {
+            Spelling::Builtin(words) => words.write_ordinal(digits, variation, output),
+            Spelling::Custom(_, speller) => speller.write_ordinal(digits, variation, output),
+        }
+    }
+
+    pub(crate) fn ordinal_suffix(self, digits: &str) -> &'a str {
+        match self {
+            Spelling::Builtin(words) => words.ordinal_suffix(digits),
+            Spelling::Custom(_, speller) => speller.ordinal_suffix(digits),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::casing::Casing;
+
+    fn cardinal(language: WordLanguage, digits: &str) -> String {
+        let mut output = String::new();
+        language.write_cardinal(digits, &mut output);
+        output
+    }
+
+    // our words for the language
+    fn words(language: Option<&Language>, traditional: bool) -> WordLanguage {
+        match Spelling::new(language, &[], traditional) {
+            Spelling::Builtin(words) => words,
+            Spelling::Custom(..) => unreachable!(),
+        }
+    }
+
+    // counts in a made-up language, where numbers are the digits with a
+    // dot after them
+    struct Dotted;
+
+    impl NumberSpeller for Dotted {
+        fn write_cardinal(&self, digits: &str, output: &mut String) {
+            output.push_str(digits);
+            output.push('.');
+        }
+
+        fn negative_word(&self) -> &str {
+            "neg "
+        }
+    }
+
+    #[test]
+    fn test_custom_speller() {
+        let spellers = [(Language::parse("cy").unwrap(), Speller(Arc::new(Dotted)))];
+        let welsh = Language::parse("cy-GB").unwrap();
+        let spelling = Spelling::new(Some(&welsh), &spellers, false);
+        let mut output = String::new();
+        spelling.write_cardinal("12", &mut output);
+        assert_eq!(output, "12.");
+        assert_eq!(spelling.negative_word(), "neg ");
+        assert_eq!(spelling.identifier(), langid!("cy"));
+        // ordinals default to cardinals, without a suffix
+        output.clear();
+        spelling.write_ordinal("12", None, &mut output);
+        assert_eq!(output, "12.");
+        assert_eq!(spelling.ordinal_suffix("12"), "");
+        // other languages are unaffected
+        let german = Language::parse("de").unwrap();
+        assert!(matches!(
+            Spelling::new(Some(&german), &spellers, false),
+            Spelling::Builtin(WordLanguage::German)
+        ));
+    }
+
+    #[test]
+    fn test_custom_speller_replaces_ours() {
+        let spellers = [(Language::parse("de").unwrap(), Speller(Arc::new(Dotted)))];
+        let german = Language::parse("de-AT").unwrap();
+        assert!(matches!(
+            Spelling::new(Some(&german), &spellers, false),
+            Spelling::Custom(..)
+        ));
+    }
+
+    #[test]
+    fn test_language() {
+        let german = Language::parse("de-AT").unwrap();
+        assert_eq!(words(Some(&german), false), WordLanguage::German);
+        assert_eq!(words(Some(&german), true), WordLanguage::German);
+        let french = Language::parse("fr").unwrap();
+        assert_eq!(words(Some(&french), false), WordLanguage::English);
+        assert_eq!(words(None, false), WordLanguage::English);
+        // nothing on the chain of pt-BR is supported
+        let portuguese = Language::parse("pt-BR").unwrap();
+        assert_eq!(words(Some(&portuguese), false), WordLanguage::English);
+        let swiss = Language::parse("de-CH").unwrap();
+        assert_eq!(words(Some(&swiss), false), WordLanguage::SwissGerman);
+        let liechtenstein = Language::parse("de-LI").unwrap();
+        assert_eq!(
+            words(Some(&liechtenstein), false),
+            WordLanguage::SwissGerman
+        );
+        let swiss = Language::parse("de-Latn-CH").unwrap();
+        assert_eq!(words(Some(&swiss), false), WordLanguage::SwissGerman);
+        let american = Language::parse("en-US").unwrap();
+        assert_eq!(words(Some(&american), false), WordLanguage::AmericanEnglish);
+        let british = Language::parse("en-GB").unwrap();
+        assert_eq!(words(Some(&british), false), WordLanguage::English);
+        let korean = Language::parse("ko-KR").unwrap();
+        assert_eq!(words(Some(&korean), false), WordLanguage::Korean);
+        assert_eq!(words(Some(&korean), true), WordLanguage::NativeKorean);
+    }
+
+    #[test]
+    fn test_casing() {
+        let english = WordLanguage::English.identifier();
+        let mut words = cardinal(WordLanguage::English, "1123");
+        Casing::Title.apply(&mut words, &english);
+        assert_eq!(words, "One Thousand One Hundred and Twenty-three");
+        Casing::Upper.apply(&mut words, &english);
+        assert_eq!(words, "ONE THOUSAND ONE HUNDRED AND TWENTY-THREE");
+    }
+
+    #[test]
+    fn test_casing_non_ascii() {
+        let mut words = cardinal(WordLanguage::German, "2000055");
+        Casing::Upper.apply(&mut words, &WordLanguage::German.identifier());
+        assert_eq!(words, "ZWEI MILLIONEN FÜNFUNDFÜNFZIG");
+    }
+
+    #[test]
+    fn test_max_len() {
+        for language in [
+            WordLanguage::English,
+            WordLanguage::AmericanEnglish,
+            WordLanguage::German,
+            WordLanguage::SwissGerman,
+            WordLanguage::Korean,
+            WordLanguage::NativeKorean,
+        ] {
+            for digits in [
+                "7",
+                "77",
+                "777",
+                "7777",
+                "77777",
+                "777777",
+                "7777777777777777777",
+                "7777777777777777777777777777777777777777",
+                "7000000000000000000000000000000000000000000000000000000000000000000007",
+            ] {
+                let words = cardinal(language, digits);
+                assert!(words.len() <= max_len(digits.len()), "{}", words);
+                let mut upper = words.clone();
+                Casing::Upper.apply(&mut upper, &language.identifier());
+                assert_eq!(upper.len(), words.len());
+            }
+            assert!(cardinal(language, "0").len() <= max_len(0));
+        }
+    }
+}
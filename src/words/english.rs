@@ -0,0 +1,323 @@
+// Spelling out numbers in English.
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+// the names of the powers of a thousand, on the short scale. Beyond the
+// last one we repeat it, as in "one thousand decillion".
+const SCALES: [&str; 12] = [
+    "",
+    "thousand",
+    "million",
+    "billion",
+    "trillion",
+    "quadrillion",
+    "quintillion",
+    "sextillion",
+    "septillion",
+    "octillion",
+    "nonillion",
+    "decillion",
+];
+
+// append the cardinal number for the ascii digits to output, as in "one
+// hundred and twenty-three". Without and, the number is in the American
+// style, which leaves out the "and": "one hundred twenty-three".
+pub(super) fn write_cardinal(digits: &str, and: bool, output: &mut String) {
+    let digits = digits.trim_start_matches('0');
+    let mut words = Words {
+        start: output.len(),
+        output,
+        and,
+    };
+    if digits.is_empty() {
+        words.push(ONES[0]);
+    } else {
+        words.write(digits);
+    }
+}
+
+// append the ordinal number for the ascii digits to output, as in "one
+// hundred and twenty-third", with and as for cardinal numbers
+pub(super) fn write_ordinal(digits: &str, and: bool, output: &mut String) {
+    write_cardinal(digits, and, output);
+    // only the last word changes, where a word can end in a hyphen, as in
+    // "twenty-one"
+    let start = output.rfind([' ', '-']).map_or(0, |index| index + 1);
+    let ordinal = match &output[start..] {
+        "one" => "first",
+        "two" => "second",
+        "three" => "third",
+        "five" => "fifth",
+        "eight" => "eighth",
+        "nine" => "ninth",
+        "twelve" => "twelfth",
+        _ => {
+            if output.ends_with('y') {
+                output.pop();
+                output.push_str("ieth");
+            } else {
+                output.push_str("th");
+            }
+            return;
+        }
+    };
+    output.truncate(start);
+    output.push_str(ordinal);
+}
+
+// the English suffix for an ordinal number given as ascii digits, as in
+// "1st" or "12th"
+pub(super) fn ordinal_suffix(digits: &str) -> &'static str {
+    let digits = digits.as_bytes();
+    let last = digits.last().copied().unwrap_or(b'0');
+    let tens = digits
+        .len()
+        .checked_sub(2)
+        .map_or(b'0', |index| digits[index]);
+    match (tens, last) {
+        (b'1', _) => "th",
+        (_, b'1') => "st",
+        (_, b'2') => "nd",
+        (_, b'3') => "rd",
+        _ => "th",
+    }
+}
+
+struct Words<'a> {
+    output: &'a mut String,
+    // where our words start in the output, so we know whether we're
+    // writing the first one
+    start: usize,
+    // whether "and" goes before the tens, as in British English
+    and: bool,
+}
+
+impl Words<'_> {
+    fn is_empty(&self) -> bool {
+        self.output.len() == self.start
+    }
+
+    fn push_and(&mut self) {
+        if self.and {
+            self.push("and");
+        }
+    }
+
+    fn push(&mut self, word: &str) {
+        if !self.is_empty() {
+            self.output.push(' ');
+        }
+        self.output.push_str(word);
+    }
+
+    // write digits without leading zeros
+    fn write(&mut self, digits: &str) {
+        // the groups we can name directly; anything above that is spelled
+        // out as a multiple of the largest scale
+        let max_digits = (SCALES.len() - 1) * 3;
+        if digits.len() > max_digits + 3 {
+            let (high, low) = digits.split_at(digits.len() - max_digits);
+            self.write(high);
+            self.push(SCALES[SCALES.len() - 1]);
+            self.write_groups(low);
+        } else {
+            self.write_groups(digits);
+        }
+    }
+
+    // write digits in groups of three, skipping groups that are zero
+    fn write_groups(&mut self, digits: &str) {
+        let group_count = digits.len().div_ceil(3);
+        let mut end = digits.len() - (group_count - 1) * 3;
+        let mut start = 0;
+        for scale in (0..group_count).rev() {
+            let group = digits[start..end]
+                .bytes()
+                .fold(0, |value, digit| value * 10 + (digit - b'0') as usize);
+            if group != 0 {
+                // the last group gets an "and" if it's below a hundred, as
+                // in "one thousand and one"
+                if scale == 0 && group < 100 && !self.is_empty() {
+                    self.push_and();
+                }
+                self.write_group(group);
+                if scale > 0 {
+                    self.push(SCALES[scale]);
+                }
+            }
+            start = end;
+            end += 3;
+        }
+    }
+
+    // write a number below a thousand
+    fn write_group(&mut self, group: usize) {
+        let (hundreds, rest) = (group / 100, group % 100);
+        if hundreds > 0 {
+            self.push(ONES[hundreds]);
+            self.push("hundred");
+            if rest > 0 {
+                self.push_and();
+            }
+        }
+        if rest >= 20 {
+            self.push(TENS[rest / 10]);
+            if rest % 10 > 0 {
+                self.output.push('-');
+                self.output.push_str(ONES[rest % 10]);
+            }
+        } else if rest > 0 {
+            self.push(ONES[rest]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cardinal(digits: &str) -> String {
+        let mut output = String::new();
+        write_cardinal(digits, true, &mut output);
+        output
+    }
+
+    fn american(digits: &str) -> String {
+        let mut output = String::new();
+        write_cardinal(digits, false, &mut output);
+        output
+    }
+
+    #[test]
+    fn test_small_numbers() {
+        assert_eq!(cardinal("0"), "zero");
+        assert_eq!(cardinal("7"), "seven");
+        assert_eq!(cardinal("13"), "thirteen");
+        assert_eq!(cardinal("20"), "twenty");
+        assert_eq!(cardinal("42"), "forty-two");
+    }
+
+    #[test]
+    fn test_hundreds() {
+        assert_eq!(cardinal("100"), "one hundred");
+        assert_eq!(cardinal("123"), "one hundred and twenty-three");
+        assert_eq!(cardinal("910"), "nine hundred and ten");
+    }
+
+    #[test]
+    fn test_scales() {
+        assert_eq!(cardinal("1000"), "one thousand");
+        assert_eq!(cardinal("1001"), "one thousand and one");
+        assert_eq!(cardinal("1101"), "one thousand one hundred and one");
+        assert_eq!(cardinal("2000000"), "two million");
+        assert_eq!(cardinal("3000045"), "three million and forty-five");
+        assert_eq!(
+            cardinal("1000000000000000000000000000000000"),
+            "one decillion"
+        );
+    }
+
+    #[test]
+    fn test_american() {
+        assert_eq!(american("123"), "one hundred twenty-three");
+        assert_eq!(american("1001"), "one thousand one");
+        assert_eq!(american("3000045"), "three million forty-five");
+        assert_eq!(american("100"), "one hundred");
+        let mut output = String::new();
+        write_ordinal("101", false, &mut output);
+        assert_eq!(output, "one hundred first");
+    }
+
+    #[test]
+    fn test_beyond_largest_scale() {
+        let mut digits = "1".to_string();
+        digits.push_str(&"0".repeat(36));
+        assert_eq!(cardinal(&digits), "one thousand decillion");
+        let mut digits = "2".to_string();
+        digits.push_str(&"0".repeat(65));
+        digits.push('3');
+        assert_eq!(cardinal(&digits), "two decillion decillion and three");
+    }
+
+    #[test]
+    fn test_leading_zeros() {
+        assert_eq!(cardinal("000"), "zero");
+        assert_eq!(cardinal("012"), "twelve");
+    }
+
+    #[test]
+    fn test_appends() {
+        let mut output = "minus ".to_string();
+        write_cardinal("1", true, &mut output);
+        assert_eq!(output, "minus one");
+    }
+
+    fn ordinal(digits: &str) -> String {
+        let mut output = String::new();
+        write_ordinal(digits, true, &mut output);
+        output
+    }
+
+    #[test]
+    fn test_ordinal() {
+        assert_eq!(ordinal("0"), "zeroth");
+        assert_eq!(ordinal("1"), "first");
+        assert_eq!(ordinal("2"), "second");
+        assert_eq!(ordinal("4"), "fourth");
+        assert_eq!(ordinal("12"), "twelfth");
+        assert_eq!(ordinal("20"), "twentieth");
+        assert_eq!(ordinal("21"), "twenty-first");
+        assert_eq!(ordinal("100"), "one hundredth");
+        assert_eq!(ordinal("1003"), "one thousand and third");
+    }
+
+    #[test]
+    fn test_ordinal_max_len() {
+        for digits in ["2", "22", "70", "102", "7777777"] {
+            assert!(
+                ordinal(digits).len()
+                    <= cardinal(digits).len() + crate::words::ORDINAL_MAX_EXTRA_LEN,
+                "{}",
+                digits
+            );
+        }
+    }
+
+    #[test]
+    fn test_ordinal_suffix() {
+        assert_eq!(ordinal_suffix("1"), "st");
+        assert_eq!(ordinal_suffix("2"), "nd");
+        assert_eq!(ordinal_suffix("3"), "rd");
+        assert_eq!(ordinal_suffix("4"), "th");
+        assert_eq!(ordinal_suffix("0"), "th");
+        assert_eq!(ordinal_suffix("11"), "th");
+        assert_eq!(ordinal_suffix("12"), "th");
+        assert_eq!(ordinal_suffix("101"), "st");
+        assert_eq!(ordinal_suffix("111"), "th");
+    }
+}
@@ -0,0 +1,352 @@
+// Spelling out numbers in German. Numbers below a million are written as a
+// single word ("einhundertdreiundzwanzig"); the larger scales are separate
+// words ("zwei millionen"). Everything is lower case, like for English;
+// casing is applied afterwards.
+
+const ONES: [&str; 20] = [
+    "null",
+    "eins",
+    "zwei",
+    "drei",
+    "vier",
+    "fünf",
+    "sechs",
+    "sieben",
+    "acht",
+    "neun",
+    "zehn",
+    "elf",
+    "zwölf",
+    "dreizehn",
+    "vierzehn",
+    "fünfzehn",
+    "sechzehn",
+    "siebzehn",
+    "achtzehn",
+    "neunzehn",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "zwanzig", "dreißig", "vierzig", "fünfzig", "sechzig", "siebzig", "achtzig", "neunzig",
+];
+
+// the scales from a million up, on the long scale, in the singular and the
+// plural. Each is a thousand times the one before. Beyond the last one we
+// repeat it.
+const SCALES: [(&str, &str); 10] = [
+    ("million", "millionen"),
+    ("milliarde", "milliarden"),
+    ("billion", "billionen"),
+    ("billiarde", "billiarden"),
+    ("trillion", "trillionen"),
+    ("trilliarde", "trilliarden"),
+    ("quadrillion", "quadrillionen"),
+    ("quadrilliarde", "quadrilliarden"),
+    ("quintillion", "quintillionen"),
+    ("quintilliarde", "quintilliarden"),
+];
+
+// ordinal numbers in digits are followed by a full stop, as in "1."
+pub(super) const ORDINAL_SUFFIX: &str = ".";
+
+// append the cardinal number for the ascii digits to output, as in
+// "einhundertdreiundzwanzig". Without sharp_s, "ß" is written "ss", as in
+// Switzerland: "dreissig".
+pub(super) fn write_cardinal(digits: &str, sharp_s: bool, output: &mut String) {
+    let start = output.len();
+    let digits = digits.trim_start_matches('0');
+    let mut words = Words { start, output };
+    if digits.is_empty() {
+        words.start_word();
+        words.output.push_str(ONES[0]);
+    } else {
+        words.write(digits, "eins");
+    }
+    if !sharp_s && output[start..].contains('ß') {
+        // both take two bytes, so the length doesn't change
+        let words = output[start..].replace('ß', "ss");
+        output.truncate(start);
+        output.push_str(&words);
+    }
+}
+
+// append the ordinal number for the ascii digits to output, as in
+// "dreiundzwanzigste". The variation gives the ending to use instead of
+// "-e", for the other cases and genders: "-er", "-es", "-en" or "-em".
+pub(super) fn write_ordinal(
+    digits: &str,
+    variation: Option<&str>,
+    sharp_s: bool,
+    output: &mut String,
+) {
+    write_ordinal_stem(digits, sharp_s, output);
+    output.push('e');
+    let ending = variation.map(|variation| variation.strip_prefix('-').unwrap_or(variation));
+    if let Some(ending @ ("er" | "es" | "en" | "em")) = ending {
+        output.push_str(&ending[1..]);
+    }
+}
+
+// the ordinal number without its final e
+fn write_ordinal_stem(digits: &str, sharp_s: bool, output: &mut String) {
+    write_cardinal(digits, sharp_s, output);
+    let digits = digits.trim_start_matches('0');
+    let below_hundred = value(&digits[digits.len().saturating_sub(2)..]);
+    if digits.is_empty() || (1..20).contains(&below_hundred) {
+        // the numbers below twenty get "te", some of them irregularly
+        for (cardinal, ordinal) in [
+            ("eins", "erst"),
+            ("drei", "dritt"),
+            ("sieben", "siebt"),
+            ("acht", "acht"),
+        ] {
+            if let Some(stem) = output.strip_suffix(cardinal) {
+                output.truncate(stem.len());
+                output.push_str(ordinal);
+                return;
+            }
+        }
+        output.push('t');
+        return;
+    }
+    // the others get "ste", where the scales are formed from the singular
+    // without a final e, as in "millionste" and "milliardste"
+    for (singular, plural) in SCALES {
+        if let Some(stem) = output.strip_suffix(plural) {
+            output.truncate(stem.len());
+            output.push_str(singular);
+            break;
+        }
+    }
+    if output.ends_with('e') {
+        output.pop();
+    }
+    output.push_str("st");
+}
+
+// the value of at most a few ascii digits
+fn value(digits: &str) -> usize {
+    digits
+        .bytes()
+        .fold(0, |value, digit| value * 10 + (digit - b'0') as usize)
+}
+
+struct Words<'a> {
+    output: &'a mut String,
+    // where our words start in the output, so we know whether we're
+    // writing the first one
+    start: usize,
+}
+
+impl Words<'_> {
+    fn start_word(&mut self) {
+        if self.output.len() > self.start {
+            self.output.push(' ');
+        }
+    }
+
+    // write digits without leading zeros, where a final 1 is written as
+    // one: "eins" by itself, but "eine" before a scale (as in "eine
+    // million")
+    fn write(&mut self, digits: &str, one: &str) {
+        let (millions, below) = digits.split_at(digits.len().saturating_sub(6));
+        if !millions.is_empty() {
+            self.write_millions(millions);
+        }
+        let below = value(below);
+        if below > 0 {
+            self.start_word();
+            self.write_below_million(below, one);
+        }
+    }
+
+    // write a number of millions, given as digits without leading zeros
+    fn write_millions(&mut self, digits: &str) {
+        let max_digits = SCALES.len() * 3;
+        let digits = if digits.len() > max_digits {
+            // anything above the largest scale is a count of that scale
+            let (high, low) = digits.split_at(digits.len() - (max_digits - 3));
+            self.write_scaled(high, SCALES.len() - 1);
+            low
+        } else {
+            digits
+        };
+        let group_count = digits.len().div_ceil(3);
+        let mut end = digits.len() - (group_count - 1) * 3;
+        let mut start = 0;
+        for scale in (0..group_count).rev() {
+            let group = digits[start..end].trim_start_matches('0');
+            if !group.is_empty() {
+                self.write_scaled(group, scale);
+            }
+            start = end;
+            end += 3;
+        }
+    }
+
+    // write a count of the scale, as in "zwei millionen"
+    fn write_scaled(&mut self, count: &str, scale: usize) {
+        let (singular, plural) = SCALES[scale];
+        if count == "1" {
+            self.start_word();
+            self.output.push_str("eine");
+            self.start_word();
+            self.output.push_str(singular);
+        } else {
+            self.write(count, "eine");
+            self.start_word();
+            self.output.push_str(plural);
+        }
+    }
+
+    fn write_below_million(&mut self, n: usize, one: &str) {
+        let (thousands, rest) = (n / 1000, n % 1000);
+        if thousands > 0 {
+            self.write_below_thousand(thousands, "ein");
+            self.output.push_str("tausend");
+        }
+        if rest > 0 {
+            self.write_below_thousand(rest, one);
+        }
+    }
+
+    fn write_below_thousand(&mut self, n: usize, one: &str) {
+        let (hundreds, rest) = (n / 100, n % 100);
+        if hundreds > 0 {
+            self.output
+                .push_str(if hundreds == 1 { "ein" } else { ONES[hundreds] });
+            self.output.push_str("hundert");
+        }
+        match rest {
+            0 => {}
+            1 => self.output.push_str(one),
+            2..=19 => self.output.push_str(ONES[rest]),
+            _ => {
+                // the units come first, as in "dreiundzwanzig"
+                let units = rest % 10;
+                if units > 0 {
+                    self.output
+                        .push_str(if units == 1 { "ein" } else { ONES[units] });
+                    self.output.push_str("und");
+                }
+                self.output.push_str(TENS[rest / 10]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cardinal(digits: &str) -> String {
+        let mut output = String::new();
+        write_cardinal(digits, true, &mut output);
+        output
+    }
+
+    fn ordinal(digits: &str) -> String {
+        let mut output = String::new();
+        write_ordinal(digits, None, true, &mut output);
+        output
+    }
+
+    #[test]
+    fn test_small_numbers() {
+        assert_eq!(cardinal("0"), "null");
+        assert_eq!(cardinal("1"), "eins");
+        assert_eq!(cardinal("16"), "sechzehn");
+        assert_eq!(cardinal("21"), "einundzwanzig");
+        assert_eq!(cardinal("30"), "dreißig");
+    }
+
+    #[test]
+    fn test_compounds() {
+        assert_eq!(cardinal("101"), "einhunderteins");
+        assert_eq!(cardinal("123"), "einhundertdreiundzwanzig");
+        assert_eq!(cardinal("1001"), "eintausendeins");
+        assert_eq!(
+            cardinal("234567"),
+            "zweihundertvierunddreißigtausendfünfhundertsiebenundsechzig"
+        );
+    }
+
+    #[test]
+    fn test_scales() {
+        assert_eq!(cardinal("1000000"), "eine million");
+        assert_eq!(cardinal("1000001"), "eine million eins");
+        assert_eq!(cardinal("2500000"), "zwei millionen fünfhunderttausend");
+        assert_eq!(cardinal("1000000000"), "eine milliarde");
+        assert_eq!(cardinal("21000000000000"), "einundzwanzig billionen");
+    }
+
+    #[test]
+    fn test_beyond_largest_scale() {
+        let mut digits = "2".to_string();
+        digits.push_str(&"0".repeat(33));
+        assert_eq!(cardinal(&digits), "zwei quintilliarden");
+        let mut digits = "1".to_string();
+        digits.push_str(&"0".repeat(36));
+        assert_eq!(cardinal(&digits), "eintausend quintilliarden");
+    }
+
+    #[test]
+    fn test_ordinal() {
+        assert_eq!(ordinal("0"), "nullte");
+        assert_eq!(ordinal("1"), "erste");
+        assert_eq!(ordinal("2"), "zweite");
+        assert_eq!(ordinal("3"), "dritte");
+        assert_eq!(ordinal("7"), "siebte");
+        assert_eq!(ordinal("8"), "achte");
+        assert_eq!(ordinal("19"), "neunzehnte");
+        assert_eq!(ordinal("20"), "zwanzigste");
+        assert_eq!(ordinal("101"), "einhunderterste");
+        assert_eq!(ordinal("1000"), "eintausendste");
+        assert_eq!(ordinal("2000000"), "zwei millionste");
+        assert_eq!(ordinal("1000000000"), "eine milliardste");
+    }
+
+    #[test]
+    fn test_ordinal_variation() {
+        let variation = |digits, variation| {
+            let mut output = String::new();
+            write_ordinal(digits, Some(variation), true, &mut output);
+            output
+        };
+        assert_eq!(variation("1", "-er"), "erster");
+        assert_eq!(variation("3", "-es"), "drittes");
+        assert_eq!(variation("20", "en"), "zwanzigsten");
+        assert_eq!(variation("2", "-e"), "zweite");
+        // variations we don't know are ignored
+        assert_eq!(variation("2", "-º"), "zweite");
+    }
+
+    #[test]
+    fn test_without_sharp_s() {
+        let mut output = "> ".to_string();
+        write_cardinal("234530", false, &mut output);
+        assert_eq!(
+            output,
+            "> zweihundertvierunddreissigtausendfünfhundertdreissig"
+        );
+        output.clear();
+        write_ordinal("30", Some("-er"), false, &mut output);
+        assert_eq!(output, "dreissigster");
+        output.clear();
+        write_cardinal("21", false, &mut output);
+        assert_eq!(output, "einundzwanzig");
+    }
+
+    #[test]
+    fn test_ordinal_max_len() {
+        for digits in ["0", "1", "20", "2000000", "7777777"] {
+            let mut output = String::new();
+            write_ordinal(digits, Some("-er"), true, &mut output);
+            assert!(
+                output.len() <= cardinal(digits).len() + crate::words::ORDINAL_MAX_EXTRA_LEN,
+                "{}",
+                digits
+            );
+        }
+    }
+}
@@ -0,0 +1,256 @@
+// Spelling out numbers in Korean, in the Sino-Korean numerals ("백이십삼")
+// or, with the traditional format modifier, the native Korean ones
+// ("스물셋"). Native numerals only go up to ninety-nine, so the hundreds
+// and up are Sino-Korean even then ("백스물셋"). Like the CJK numerals,
+// the places repeat for every four digits, which are followed by the
+// myriad they count; these groups are separated by spaces.
+
+const DIGITS: [&str; 10] = ["영", "일", "이", "삼", "사", "오", "육", "칠", "팔", "구"];
+
+// ten, hundred and thousand
+const PLACES: [&str; 3] = ["십", "백", "천"];
+
+// ten thousand, and every four digits beyond it. Beyond the last one we
+// repeat it, as for the scales of other languages.
+const MYRIADS: [&str; 12] = [
+    "만", "억", "조", "경", "해", "자", "양", "구", "간", "정", "재", "극",
+];
+
+const NATIVE_UNITS: [&str; 10] = [
+    "", "하나", "둘", "셋", "넷", "다섯", "여섯", "일곱", "여덟", "아홉",
+];
+
+const NATIVE_TENS: [&str; 10] = [
+    "", "열", "스물", "서른", "마흔", "쉰", "예순", "일흔", "여든", "아흔",
+];
+
+// ordinal numbers in digits are followed by this, as in "1번째"
+pub(super) const ORDINAL_SUFFIX: &str = "번째";
+
+pub(super) const NEGATIVE_WORD: &str = "마이너스 ";
+
+// append the Sino-Korean cardinal number for the ascii digits to output,
+// as in "백이십삼만 사천오백육십칠"
+pub(super) fn write_cardinal(digits: &str, output: &mut String) {
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        output.push_str(DIGITS[0]);
+    } else {
+        Words {
+            start: output.len(),
+            output,
+        }
+        .write(digits);
+    }
+}
+
+// append the Sino-Korean ordinal number for the ascii digits to output,
+// as in "제이십삼"
+pub(super) fn write_ordinal(digits: &str, output: &mut String) {
+    output.push('제');
+    write_cardinal(digits, output);
+}
+
+// append the native Korean cardinal number for the ascii digits to
+// output, as in "스물셋"
+pub(super) fn write_native_cardinal(digits: &str, output: &mut String) {
+    let digits = digits.trim_start_matches('0');
+    let (hundreds, below) = digits.split_at(digits.len().saturating_sub(2));
+    let below = value(below);
+    if !hundreds.is_empty() {
+        let mut hundreds = hundreds.to_string();
+        hundreds.push_str("00");
+        write_cardinal(&hundreds, output);
+    }
+    if below == 0 {
+        if hundreds.is_empty() {
+            output.push_str(DIGITS[0]);
+        }
+        return;
+    }
+    // the Sino-Korean part ended with a myriad, so this is a new group
+    if !hundreds.is_empty() && value(&digits[digits.len().saturating_sub(4)..]) < 100 {
+        output.push(' ');
+    }
+    output.push_str(NATIVE_TENS[below / 10]);
+    output.push_str(NATIVE_UNITS[below % 10]);
+}
+
+// append the native Korean ordinal number for the ascii digits to output,
+// as in "스물셋째". One and two on their own are irregular ("첫째",
+// "둘째"); at the end of a longer number, one, two and twenty are
+// shortened ("열한째", "열두째", "스무째").
+pub(super) fn write_native_ordinal(digits: &str, output: &mut String) {
+    let start = output.len();
+    write_native_cardinal(digits, output);
+    if &output[start..] == "하나" {
+        output.truncate(start);
+        output.push('첫');
+    } else if &output[start..] != "둘" {
+        for (cardinal, shortened) in [("하나", "한"), ("둘", "두"), ("스물", "스무")] {
+            if let Some(stem) = output.strip_suffix(cardinal) {
+                output.truncate(stem.len());
+                output.push_str(shortened);
+                break;
+            }
+        }
+    }
+    output.push('째');
+}
+
+// the value of at most a few ascii digits
+fn value(digits: &str) -> usize {
+    digits
+        .bytes()
+        .fold(0, |value, digit| value * 10 + (digit - b'0') as usize)
+}
+
+struct Words<'a> {
+    output: &'a mut String,
+    // where our words start in the output, so we know whether we're
+    // writing the first one
+    start: usize,
+}
+
+impl Words<'_> {
+    fn start_word(&mut self) {
+        if self.output.len() > self.start {
+            self.output.push(' ');
+        }
+    }
+
+    // write digits without leading zeros
+    fn write(&mut self, digits: &str) {
+        let max_digits = (MYRIADS.len() + 1) * 4;
+        let digits = if digits.len() > max_digits {
+            // anything above the largest myriad is a count of it
+            let (high, low) = digits.split_at(digits.len() - (max_digits - 4));
+            self.write(high);
+            self.output.push_str(MYRIADS[MYRIADS.len() - 1]);
+            low
+        } else {
+            digits
+        };
+        let group_count = digits.len().div_ceil(4);
+        let mut end = digits.len() - (group_count - 1) * 4;
+        let mut start = 0;
+        for myriad in (0..group_count).rev() {
+            let group = digits[start..end].trim_start_matches('0');
+            if !group.is_empty() {
+                self.start_word();
+                // ten thousand is "만" rather than "일만", but the larger
+                // myriads keep their one ("일억")
+                if group != "1" || myriad != 1 {
+                    self.write_group(group);
+                }
+                if myriad > 0 {
+                    self.output.push_str(MYRIADS[myriad - 1]);
+                }
+            }
+            start = end;
+            end += 4;
+        }
+    }
+
+    // write up to four digits without leading zeros, where a one before a
+    // place is left out ("십" rather than "일십")
+    fn write_group(&mut self, group: &str) {
+        for (index, digit) in group.bytes().enumerate() {
+            let digit = usize::from(digit - b'0');
+            let place = group.len() - 1 - index;
+            if digit == 0 {
+                continue;
+            }
+            if digit > 1 || place == 0 {
+                self.output.push_str(DIGITS[digit]);
+            }
+            if place > 0 {
+                self.output.push_str(PLACES[place - 1]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cardinal(digits: &str) -> String {
+        let mut output = String::new();
+        write_cardinal(digits, &mut output);
+        output
+    }
+
+    fn native(digits: &str) -> String {
+        let mut output = String::new();
+        write_native_cardinal(digits, &mut output);
+        output
+    }
+
+    fn native_ordinal(digits: &str) -> String {
+        let mut output = String::new();
+        write_native_ordinal(digits, &mut output);
+        output
+    }
+
+    #[test]
+    fn test_cardinal() {
+        assert_eq!(cardinal("0"), "영");
+        assert_eq!(cardinal("1"), "일");
+        assert_eq!(cardinal("10"), "십");
+        assert_eq!(cardinal("123"), "백이십삼");
+        assert_eq!(cardinal("2024"), "이천이십사");
+    }
+
+    #[test]
+    fn test_myriads() {
+        assert_eq!(cardinal("10000"), "만");
+        assert_eq!(cardinal("10001"), "만 일");
+        assert_eq!(cardinal("1234567"), "백이십삼만 사천오백육십칠");
+        assert_eq!(cardinal("100000000"), "일억");
+        assert_eq!(cardinal("100010000"), "일억 만");
+        assert_eq!(cardinal("1000000000000"), "일조");
+    }
+
+    #[test]
+    fn test_beyond_largest_myriad() {
+        let mut digits = "2".to_string();
+        digits.push_str(&"0".repeat(48));
+        assert_eq!(cardinal(&digits), "이극");
+        let mut digits = "3".to_string();
+        digits.push_str(&"0".repeat(52));
+        assert_eq!(cardinal(&digits), "삼만극");
+    }
+
+    #[test]
+    fn test_native() {
+        assert_eq!(native("0"), "영");
+        assert_eq!(native("1"), "하나");
+        assert_eq!(native("10"), "열");
+        assert_eq!(native("23"), "스물셋");
+        assert_eq!(native("99"), "아흔아홉");
+        assert_eq!(native("100"), "백");
+        assert_eq!(native("123"), "백스물셋");
+        assert_eq!(native("10005"), "만 다섯");
+    }
+
+    #[test]
+    fn test_ordinal() {
+        let mut output = String::new();
+        write_ordinal("23", &mut output);
+        assert_eq!(output, "제이십삼");
+    }
+
+    #[test]
+    fn test_native_ordinal() {
+        assert_eq!(native_ordinal("1"), "첫째");
+        assert_eq!(native_ordinal("2"), "둘째");
+        assert_eq!(native_ordinal("3"), "셋째");
+        assert_eq!(native_ordinal("10"), "열째");
+        assert_eq!(native_ordinal("11"), "열한째");
+        assert_eq!(native_ordinal("12"), "열두째");
+        assert_eq!(native_ordinal("20"), "스무째");
+        assert_eq!(native_ordinal("21"), "스물한째");
+        assert_eq!(native_ordinal("101"), "백한째");
+    }
+}
@@ -0,0 +1,190 @@
+// The XPath layer: functions with the exact signatures and semantics of the
+// fn:format-* functions, reporting errors as XPath error codes. The rest of
+// the crate doesn't know about XPath and can be used without it; the crate
+// root is the core, and its format_integer and format_integer_lang are
+// conveniences for it that report the crate's own errors.
+use ibig::IBig;
+
+use crate::context::FormatContext;
+use crate::language::Language;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    InvalidPicture,
+    // the output exceeds the limit set on the context; the spec doesn't
+    // have a dedicated error for implementation limits
+    OutputTooLong,
+    // the picture asks for something not implemented, reported only when
+    // the context is strict
+    Unsupported,
+    // the host configured the context wrongly, like with options that
+    // conflict; this is never the stylesheet author's fault
+    Configuration,
+}
+
+impl Error {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::InvalidPicture => "FODF1310",
+            Error::OutputTooLong | Error::Unsupported | Error::Configuration => "FOER0000",
+        }
+    }
+}
+
+impl From<crate::Error> for Error {
+    fn from(error: crate::Error) -> Self {
+        match error {
+            crate::Error::InvalidPictureString(_) | crate::Error::InvalidFormatModifier(_) => {
+                Error::InvalidPicture
+            }
+            crate::Error::OutputTooLong => Error::OutputTooLong,
+            crate::Error::Unsupported(_) => Error::Unsupported,
+            // these come from building options or from the batch API, not
+            // from the picture
+            crate::Error::InvalidLanguageTag(_)
+            | crate::Error::InvalidCountry(_)
+            | crate::Error::InvalidDigitFamily(_)
+            | crate::Error::InvalidCurrencyCode(_)
+            | crate::Error::ConflictingOptions(..)
+            | crate::Error::InvalidValidityBitmap => Error::Configuration,
+        }
+    }
+}
+
+// fn:format-integer($value as xs:integer?, $picture as xs:string,
+//                   $lang as xs:string?) as xs:string
+pub fn format_integer(
+    value: Option<IBig>,
+    picture: &str,
+    lang: Option<&str>,
+) -> Result<String, Error> {
+    format_integer_with_context(&FormatContext::default(), value, picture, lang)
+}
+
+// as format_integer, but using a context shared between calls, which
+// supplies the default language and caches compiled pictures
+pub fn format_integer_with_context(
+    context: &FormatContext,
+    value: Option<IBig>,
+    picture: &str,
+    lang: Option<&str>,
+) -> Result<String, Error> {
+    // a language we can't parse is ignored, like one we don't support, and
+    // the language of the context is used instead
+    let language = lang.and_then(|lang| Language::parse(lang).ok());
+    // an empty sequence formats as the empty string, but the picture still
+    // has to be valid
+    let i = value.clone().unwrap_or_default();
+    let formatted = match &language {
+        Some(language) => context.format_integer_lang(i, picture, language),
+        None => context.format_integer(i, picture),
+    }
+    .map_err(Error::from)?;
+    Ok(if value.is_some() {
+        formatted
+    } else {
+        String::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_integer() {
+        assert_eq!(
+            format_integer(Some(1234.into()), "#,##0", None).unwrap(),
+            "1,234"
+        );
+    }
+
+    #[test]
+    fn test_format_integer_empty_sequence() {
+        assert_eq!(format_integer(None, "#,##0", None).unwrap(), "");
+    }
+
+    #[test]
+    fn test_format_integer_empty_sequence_invalid_picture() {
+        assert_eq!(format_integer(None, "0,", None), Err(Error::InvalidPicture));
+    }
+
+    #[test]
+    fn test_format_integer_invalid_picture_code() {
+        let error = format_integer(Some(1.into()), "0,,0", None).unwrap_err();
+        assert_eq!(error.code(), "FODF1310");
+    }
+
+    #[test]
+    fn test_format_integer_with_context() {
+        let context = FormatContext::default();
+        assert_eq!(
+            format_integer_with_context(&context, Some(1234.into()), "0,000", None).unwrap(),
+            "1,234"
+        );
+    }
+
+    #[test]
+    fn test_format_integer_output_too_long() {
+        let options = crate::FormatOptions::builder()
+            .max_output_len(2)
+            .build()
+            .unwrap();
+        let context = FormatContext::new(options);
+        let error = format_integer_with_context(&context, Some(123.into()), "0", None).unwrap_err();
+        assert_eq!(error, Error::OutputTooLong);
+        assert_eq!(error.code(), "FOER0000");
+    }
+
+    #[test]
+    fn test_format_integer_invalid_language_is_ignored() {
+        assert_eq!(
+            format_integer(Some(12.into()), "0", Some("not a language")).unwrap(),
+            "12"
+        );
+    }
+
+    #[test]
+    fn test_format_integer_unsupported() {
+        assert_eq!(format_integer(Some(3.into()), "z", None).unwrap(), "3");
+        let options = crate::FormatOptions::builder()
+            .strict(true)
+            .build()
+            .unwrap();
+        let context = FormatContext::new(options);
+        let error = format_integer_with_context(&context, Some(3.into()), "z", None).unwrap_err();
+        assert_eq!(error, Error::Unsupported);
+        assert_eq!(error.code(), "FOER0000");
+    }
+
+    #[test]
+    fn test_options_errors_are_not_picture_errors() {
+        for error in [
+            crate::Error::InvalidLanguageTag("?".to_string()),
+            crate::Error::InvalidCountry("?".to_string()),
+            crate::Error::InvalidDigitFamily('a'),
+            crate::Error::InvalidCurrencyCode("?".to_string()),
+            crate::Error::ConflictingOptions("strict", "extensions"),
+        ] {
+            let error = Error::from(error);
+            assert_eq!(error, Error::Configuration);
+            assert_eq!(error.code(), "FOER0000");
+        }
+        assert_eq!(
+            Error::from(crate::Picture::parse("0;x").unwrap_err()),
+            Error::InvalidPicture
+        );
+    }
+
+    #[test]
+    fn test_format_integer_lang() {
+        assert_eq!(
+            format_integer(Some(7.into()), "w", Some("de")).unwrap(),
+            "sieben"
+        );
+        assert_eq!(
+            format_integer(Some(7.into()), "w", Some("not a language")).unwrap(),
+            "seven"
+        );
+    }
+}